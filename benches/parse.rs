@@ -0,0 +1,49 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+const BATCH_SIZE: usize = 1000;
+
+fn bench_batch(c: &mut Criterion, name: &str, samples: &[&str]) {
+    let batch: Vec<&str> = samples.iter().copied().cycle().take(BATCH_SIZE).collect();
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Elements(batch.len() as u64));
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            for message in &batch {
+                let _ = black_box(wte_mt_rx_parser::parse(black_box(message)));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn rss(c: &mut Criterion) {
+    bench_batch(c, "rss", &["SS,A,123", "SS,1,123", "SS,1,255", "SS,A,000"]);
+}
+
+fn mt_structured(c: &mut Criterion) {
+    bench_batch(
+        c,
+        "mt_structured",
+        &[
+            "MT1001000AL400C592753572B323433212S1723756E4706",
+            "MT1001001TS400C592753572B300------S-------E0000",
+        ],
+    );
+}
+
+fn mt_raw(c: &mut Criterion) {
+    bench_batch(
+        c,
+        "mt_raw",
+        &[
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+            "MT6001001FFFE2FA0062C93A9AB959E55EE7788C71B791131",
+        ],
+    );
+}
+
+criterion_group!(benches, rss, mt_structured, mt_raw);
+criterion_main!(benches);
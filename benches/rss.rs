@@ -0,0 +1,30 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use wte_mt_rx_parser::rss;
+
+fn rss_nnn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rss_nnn");
+    group.bench_function("parse", |b| {
+        b.iter(|| rss::parse(black_box("SS,A,123")).map(|rss| rss.nnn))
+    });
+    group.bench_function("parse_nnn_only", |b| {
+        b.iter(|| rss::parse_nnn_only(black_box("SS,A,123")))
+    });
+    group.finish();
+}
+
+fn rss_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rss_parse");
+    group.bench_function("crate_parse", |b| {
+        b.iter(|| wte_mt_rx_parser::parse(black_box("SS,A,123")))
+    });
+    group.bench_function("parse", |b| b.iter(|| rss::parse(black_box("SS,A,123"))));
+    group.bench_function("parse_rss_fast", |b| {
+        b.iter(|| rss::parse_rss_fast(black_box(b"SS,A,123")))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, rss_nnn, rss_parse);
+criterion_main!(benches);
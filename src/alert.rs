@@ -0,0 +1,86 @@
+//! Combined MT(1) and MT(6) alerts
+//!
+//! When the MT-RX outputs both the structured and the raw data packet for the same alert, they
+//! carry complementary information (e.g. signal strength and location in MT(1), the full 406 MHz
+//! message in MT(6)). [`merge_alert`] pairs them into an [`EnrichedAlert`].
+
+use crate::mt_raw::MtRaw;
+use crate::mt_structured::MtStructured;
+
+/// A MT(1) and MT(6) message pair describing the same alert.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnrichedAlert {
+    /// Structured message.
+    pub structured: MtStructured,
+
+    /// Raw data message.
+    pub raw: MtRaw,
+
+    /// Whether the beacon hex code of the structured message matches the one decoded from the
+    /// raw data.
+    pub checksum_match: bool,
+}
+
+/// Pairs `structured` and `raw` if they belong to the same alert, i.e. they have the same
+/// MT-RX ID and sequence number. Returns `None` otherwise.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{alert, mt_raw, mt_structured};
+/// let structured = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+/// let raw = mt_raw::parse("MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+/// let alert = alert::merge_alert(&structured, &raw).unwrap();
+/// assert!(alert.checksum_match);
+/// ```
+pub fn merge_alert(structured: &MtStructured, raw: &MtRaw) -> Option<EnrichedAlert> {
+    if structured.sequence_number != raw.sequence_number || structured.id != raw.id {
+        return None;
+    }
+
+    let checksum_match = match raw.into_mt_structured_via_decode() {
+        Ok(Some(decoded)) => decoded.beacon.eq_ignore_ascii_case(&structured.beacon),
+        _ => false,
+    };
+
+    Some(EnrichedAlert {
+        structured: structured.clone(),
+        raw: raw.clone(),
+        checksum_match,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mt_raw, mt_structured};
+
+    #[test]
+    fn matching_pair() {
+        let structured =
+            mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let raw = mt_raw::parse("MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+
+        let alert = merge_alert(&structured, &raw).unwrap();
+        assert_eq!(alert.structured, structured);
+        assert_eq!(alert.raw, raw);
+        assert!(alert.checksum_match);
+
+        // same id and sequence number, but a different beacon
+        let raw = mt_raw::parse("MT6001000FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert!(!merge_alert(&structured, &raw).unwrap().checksum_match);
+    }
+
+    #[test]
+    fn mismatching_pair() {
+        let structured =
+            mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+
+        // different sequence number
+        let raw = mt_raw::parse("MT6001001FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+        assert!(merge_alert(&structured, &raw).is_none());
+
+        // different id
+        let raw = mt_raw::parse("MT6002000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+        assert!(merge_alert(&structured, &raw).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+//! Batches of parsed messages
+//!
+//! [`MessageBatch`] packages common operations on a collection of parsed messages, such as
+//! counting messages per kind or extracting their locations.
+
+use std::collections::HashMap;
+
+use crate::position::Position;
+use crate::{MessageKind, ParsedMessage};
+
+/// A collection of parsed messages, in order.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{batch::MessageBatch, MessageKind};
+/// let batch: MessageBatch = ["SS,A,123", "SS,1,123"]
+///     .into_iter()
+///     .filter_map(|m| wte_mt_rx_parser::parse(m).ok())
+///     .collect();
+/// assert_eq!(batch.summary()[&MessageKind::Rss], 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MessageBatch(pub Vec<ParsedMessage>);
+
+impl MessageBatch {
+    /// Returns an iterator over the messages.
+    pub fn iter(&self) -> std::slice::Iter<'_, ParsedMessage> {
+        self.0.iter()
+    }
+
+    /// Returns how many messages of each kind the batch holds. Kinds without messages are
+    /// not present.
+    pub fn summary(&self) -> HashMap<MessageKind, usize> {
+        let mut counts = HashMap::new();
+        for msg in &self.0 {
+            *counts.entry(msg.kind()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the locations of the messages carrying one (see [`ParsedMessage::position`]).
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.0.iter().filter_map(ParsedMessage::position)
+    }
+}
+
+impl From<Vec<ParsedMessage>> for MessageBatch {
+    fn from(messages: Vec<ParsedMessage>) -> Self {
+        MessageBatch(messages)
+    }
+}
+
+impl FromIterator<ParsedMessage> for MessageBatch {
+    fn from_iter<I: IntoIterator<Item = ParsedMessage>>(iter: I) -> Self {
+        MessageBatch(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for MessageBatch {
+    type Item = ParsedMessage;
+    type IntoIter = std::vec::IntoIter<ParsedMessage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a MessageBatch {
+    type Item = &'a ParsedMessage;
+    type IntoIter = std::slice::Iter<'a, ParsedMessage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn summary_and_positions() {
+        let batch: MessageBatch = [
+            "MT1001000AL400C592753572B323433212S1723756E4706",
+            "MT1001001AL400C592753572B323---------------4706",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+            "SS,A,123",
+            "SS,1,123",
+            "SS,1,124",
+        ]
+        .into_iter()
+        .map(|m| parse(m).unwrap())
+        .collect();
+
+        let summary = batch.summary();
+        assert_eq!(summary[&MessageKind::Rss], 3);
+        assert_eq!(summary[&MessageKind::MtStructured], 2);
+        assert_eq!(summary[&MessageKind::MtRaw], 1);
+        assert!(!summary.contains_key(&MessageKind::Unknown));
+
+        assert_eq!(batch.positions().count(), 2);
+        assert_eq!((&batch).into_iter().count(), 6);
+        let rss = batch
+            .into_iter()
+            .filter(|m| m.kind() == MessageKind::Rss)
+            .count();
+        assert_eq!(rss, 3);
+    }
+}
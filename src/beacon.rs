@@ -0,0 +1,472 @@
+//! 406 MHz beacon hex code decoding
+//!
+//! The beacon hex code output by the MT-RX is 15 characters (60 bits) long and corresponds to
+//! bits 26 to 85 of the 406 MHz beacon message, as defined in the COSPAS-SARSAT T.001
+//! specification:
+//! - bit `26` – protocol flag (`0` for location protocols, `1` for user protocols)
+//! - bits `27-36` – country code
+//! - bits `37-40` – protocol code (location protocols)
+//! - bits `41-85` – identification data and, for location protocols, a coarse position (PDF-1)
+//!
+//! Bits are numbered as in the specification, i.e. the first bit of the beacon hex code is bit `26`.
+
+use crate::position::Position;
+use crate::ParseError;
+
+/// Number of hex characters in a beacon hex code.
+pub const BEACON_HEX_LEN: usize = 15;
+
+/// First message bit carried by the beacon hex code.
+const FIRST_BIT: usize = 26;
+
+/// Last message bit carried by the beacon hex code.
+const LAST_BIT: usize = 85;
+
+/// Default (not available) standard location PDF-1 position, bits 65-85
+/// (`0 111111111 0 1111111111`).
+const STANDARD_DEFAULT_POSITION: u64 = 0xFFBFF;
+
+/// Default (not available) national location PDF-1 position, bits 59-85
+/// (`0 1111111 00000 0 11111111 00000`).
+const NATIONAL_DEFAULT_POSITION: u64 = 0x3F81FE0;
+
+/// Auxiliary identification data carried by a beacon hex code, see [`Beacon::aux`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BeaconAux {
+    /// Maritime Mobile Service Identity (see [`Beacon::mmsi`]).
+    Mmsi(u32),
+
+    /// 24-bit aircraft address (ICAO).
+    AircraftAddress(u32),
+
+    /// Beacon serial number, with the COSPAS-SARSAT type approval certificate number if encoded.
+    Serial {
+        serial_number: u32,
+        type_approval: Option<u16>,
+    },
+
+    /// National identification number of national location protocols.
+    National(u32),
+}
+
+/// A decoded 406 MHz beacon hex code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Beacon {
+    /// Bits 26 to 85, right aligned.
+    bits: u64,
+}
+
+impl Beacon {
+    /// Tries to decode a 15 character beacon `hex` code.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::beacon::Beacon;
+    /// let beacon = Beacon::from_hex("400C592753572B3").unwrap();
+    /// assert_eq!(beacon.country_code(), 512);
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Beacon, ParseError> {
+        if hex.len() != BEACON_HEX_LEN {
+            return Err(ParseError::SizeNotMatch {
+                expected: BEACON_HEX_LEN,
+                found: hex.len(),
+            });
+        }
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError::Invalid);
+        }
+
+        let bits = u64::from_str_radix(hex, 16)?;
+        Ok(Beacon { bits })
+    }
+
+    /// Creates a beacon from message bits 26 to 85, right aligned. Higher bits are ignored.
+    pub fn from_bits(bits: u64) -> Beacon {
+        Beacon {
+            bits: bits & ((1 << (LAST_BIT - FIRST_BIT + 1)) - 1),
+        }
+    }
+
+    /// Returns the 15 Hex ID of the beacon, as used by the COSPAS-SARSAT registries (uppercase).
+    ///
+    /// This is the beacon hex code with the encoded position (if any) replaced by its default
+    /// value, so it does not change when the beacon moves. Only standard and national location
+    /// protocols are handled, other protocols are returned as is.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::beacon::Beacon;
+    /// let beacon = Beacon::from_hex("400c592753572b3").unwrap();
+    /// assert_eq!(beacon.hex_id(), "400C592752FFBFF");
+    /// ```
+    pub fn hex_id(&self) -> String {
+        let bits = if self.is_standard_location() {
+            self.with_bits(65, STANDARD_DEFAULT_POSITION)
+        } else if self.is_national_location() {
+            self.with_bits(59, NATIONAL_DEFAULT_POSITION)
+        } else {
+            self.bits
+        };
+        format!("{:015X}", bits)
+    }
+
+    /// Returns the beacon bits with bits `first` to 85 replaced by `value`.
+    fn with_bits(&self, first: usize, value: u64) -> u64 {
+        let mask = (1 << (LAST_BIT - first + 1)) - 1;
+        (self.bits & !mask) | value
+    }
+
+    /// Returns message bits `first` to `last` (inclusive, numbered as in the specification).
+    ///
+    /// ## Panics
+    /// - If the range is not within bits 26 to 85.
+    pub fn bits(&self, first: usize, last: usize) -> u64 {
+        assert!(FIRST_BIT <= first && first <= last && last <= LAST_BIT);
+        let len = last - first + 1;
+        (self.bits >> (LAST_BIT - last)) & ((1 << len) - 1)
+    }
+
+    /// Returns whether the beacon uses a user protocol (protocol flag set).
+    pub fn is_user_protocol(&self) -> bool {
+        self.bits(26, 26) == 1
+    }
+
+    /// Returns whether the beacon uses a location protocol (protocol flag not set).
+    pub fn is_location_protocol(&self) -> bool {
+        !self.is_user_protocol()
+    }
+
+    /// Returns the Maritime Identification Digits country code (bits 27-36).
+    pub fn country_code(&self) -> u16 {
+        self.bits(27, 36) as u16
+    }
+
+    /// Returns the location protocol code (bits 37-40), or `None` for user protocols.
+    pub fn location_protocol_code(&self) -> Option<u8> {
+        self.is_location_protocol().then(|| self.bits(37, 40) as u8)
+    }
+
+    /// Returns whether the beacon uses one of the standard location protocols.
+    pub fn is_standard_location(&self) -> bool {
+        matches!(
+            self.location_protocol_code(),
+            Some(0b0010..=0b0111) | Some(0b1100) | Some(0b1110)
+        )
+    }
+
+    /// Returns whether the beacon uses one of the national location protocols.
+    pub fn is_national_location(&self) -> bool {
+        matches!(
+            self.location_protocol_code(),
+            Some(0b1000) | Some(0b1010) | Some(0b1011) | Some(0b1111)
+        )
+    }
+
+    /// Returns the user protocol code (bits 37-39), or `None` for location protocols.
+    pub fn user_protocol_code(&self) -> Option<u8> {
+        self.is_user_protocol().then(|| self.bits(37, 39) as u8)
+    }
+
+    /// Returns whether this is the 15 Hex ID of a second generation beacon (C/S T.018), i.e. a
+    /// user protocol beacon with protocol code `101` (bits 37-39).
+    pub fn is_second_generation(&self) -> bool {
+        self.user_protocol_code() == Some(0b101)
+    }
+
+    /// Returns the Maritime Mobile Service Identity of the beacon, if it carries one.
+    ///
+    /// The MMSI is made of the country code (Maritime Identification Digits) followed by the
+    /// last 6 digits carried by the beacon, for:
+    /// - maritime user protocol, when bits 40-75 are 6 modified-Baudot digits (not a radio call sign).
+    /// - standard location EPIRB-MMSI protocol (bits 41-60).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::beacon::Beacon;
+    /// let beacon = Beacon::from_hex("C009D6502815401").unwrap();
+    /// assert_eq!(beacon.mmsi(), Some(512123456));
+    /// ```
+    pub fn mmsi(&self) -> Option<u32> {
+        let digits = if self.user_protocol_code() == Some(0b010) {
+            (0..6).try_fold(0, |acc, i| {
+                let first = 40 + i * 6;
+                let digit = baudot_digit(self.bits(first, first + 5) as u8)?;
+                Some(acc * 10 + digit as u32)
+            })?
+        } else if self.location_protocol_code() == Some(0b0010) {
+            let digits = self.bits(41, 60) as u32;
+            if digits > 999_999 {
+                return None;
+            }
+            digits
+        } else {
+            return None;
+        };
+
+        Some(self.country_code() as u32 * 1_000_000 + digits)
+    }
+
+    /// Returns the auxiliary identification data carried by the beacon, as appropriate for its
+    /// protocol.
+    ///
+    /// The following protocols are decoded:
+    /// - maritime user and standard location EPIRB-MMSI: MMSI (see [`Beacon::mmsi`]).
+    /// - serial user: aircraft address (bits 44-67) or serial number (bits 44-63) with the type
+    ///   approval number (bits 74-83) if flagged by bit 43.
+    /// - standard location ELT 24-bit address: aircraft address (bits 41-64).
+    /// - standard location serial (ELT, EPIRB and PLB): type approval number (bits 41-50) and
+    ///   serial number (bits 51-64).
+    /// - national location: national ID (bits 41-58).
+    ///
+    /// Returns `None` for other protocols (e.g. radio call signs or aircraft operator designators).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::beacon::{Beacon, BeaconAux};
+    /// let beacon = Beacon::from_hex("C009D6502815401").unwrap();
+    /// assert_eq!(beacon.aux(), Some(BeaconAux::Mmsi(512123456)));
+    /// ```
+    pub fn aux(&self) -> Option<BeaconAux> {
+        if let Some(mmsi) = self.mmsi() {
+            return Some(BeaconAux::Mmsi(mmsi));
+        }
+
+        let bits = |first, last| self.bits(first, last) as u32;
+        let aux = match (self.user_protocol_code(), self.location_protocol_code()) {
+            // serial user, aircraft 24-bit address
+            (Some(0b011), _) if bits(40, 42) == 0b011 => BeaconAux::AircraftAddress(bits(44, 67)),
+            // serial user, aircraft operator designator
+            (Some(0b011), _) if bits(40, 42) == 0b001 => return None,
+            (Some(0b011), _) => BeaconAux::Serial {
+                serial_number: bits(44, 63),
+                type_approval: (bits(43, 43) == 1).then(|| bits(74, 83) as u16),
+            },
+            (_, Some(0b0011)) => BeaconAux::AircraftAddress(bits(41, 64)),
+            (_, Some(0b0100) | Some(0b0110) | Some(0b0111)) => BeaconAux::Serial {
+                serial_number: bits(51, 64),
+                type_approval: Some(bits(41, 50) as u16),
+            },
+            _ if self.is_national_location() => BeaconAux::National(bits(41, 58)),
+            _ => return None,
+        };
+        Some(aux)
+    }
+
+    /// Returns the coarse position encoded in the beacon hex code (PDF-1).
+    ///
+    /// Returns `None` for user protocols, for location protocols other than standard and national
+    /// location, or when the beacon transmits the default (not available) position.
+    ///
+    /// ## Notes
+    /// - Standard location protocols encode the position in 1/4 degree steps
+    ///   (bits 65-74 latitude, bits 75-85 longitude).
+    /// - National location protocols encode the position in degrees and 2 minute steps
+    ///   (bits 59-71 latitude, bits 72-85 longitude).
+    pub fn position(&self) -> Option<Position> {
+        let (latitude, longitude) = if self.is_standard_location() {
+            let lat = self.bits(66, 74);
+            let long = self.bits(76, 85);
+            if lat == 0b1_1111_1111 && long == 0b11_1111_1111 {
+                return None;
+            }
+            (lat as f64 / 4.0, long as f64 / 4.0)
+        } else if self.is_national_location() {
+            let (lat_degrees, lat_minutes) = (self.bits(60, 66), self.bits(67, 71) * 2);
+            let (long_degrees, long_minutes) = (self.bits(73, 80), self.bits(81, 85) * 2);
+            if lat_degrees == 0b111_1111 && long_degrees == 0b1111_1111 {
+                return None;
+            }
+            (
+                lat_degrees as f64 + lat_minutes as f64 / 60.0,
+                long_degrees as f64 + long_minutes as f64 / 60.0,
+            )
+        } else {
+            return None;
+        };
+
+        if latitude > 90.0 || longitude > 180.0 {
+            return None;
+        }
+
+        let (lat_flag, long_flag) = if self.is_standard_location() {
+            (self.bits(65, 65), self.bits(75, 75))
+        } else {
+            (self.bits(59, 59), self.bits(72, 72))
+        };
+        let latitude = if lat_flag == 1 { -latitude } else { latitude };
+        let longitude = if long_flag == 1 {
+            -longitude
+        } else {
+            longitude
+        };
+
+        Some(Position::new(latitude, longitude))
+    }
+}
+
+/// Returns the beacon protocol generation of a 15 character `beacon_hex` code: `1` for first
+/// generation (C/S T.001) beacons, `2` for second generation (C/S T.018) beacons.
+///
+/// The beacon hex code carries no explicit version field: second generation beacons are
+/// identified by the protocol flag (bit 26) set together with user protocol code `101`
+/// (bits 37-39), which is reserved for their 15 Hex ID. See [`Beacon::is_second_generation`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::beacon;
+/// assert_eq!(beacon::beacon_protocol_version("400C592753572B3"), Ok(1));
+/// assert!(beacon::beacon_protocol_version("400C59275357").is_err());
+/// ```
+pub fn beacon_protocol_version(beacon_hex: &str) -> Result<u8, ParseError> {
+    let beacon = Beacon::from_hex(beacon_hex)?;
+    Ok(if beacon.is_second_generation() { 2 } else { 1 })
+}
+
+/// Returns whether `beacon_hex` is the 15 Hex ID of a second generation beacon, see
+/// [`beacon_protocol_version`]. Returns `false` if it can not be decoded.
+pub fn supports_second_generation(beacon_hex: &str) -> bool {
+    beacon_protocol_version(beacon_hex) == Ok(2)
+}
+
+/// Decodes a modified-Baudot (6 bits) `code` into a decimal digit.
+fn baudot_digit(code: u8) -> Option<u8> {
+    let digit = match code {
+        0b001101 => 0,
+        0b011101 => 1,
+        0b011001 => 2,
+        0b010000 => 3,
+        0b001010 => 4,
+        0b000001 => 5,
+        0b010101 => 6,
+        0b011100 => 7,
+        0b001100 => 8,
+        0b000011 => 9,
+        _ => return None,
+    };
+    Some(digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields() {
+        let beacon = Beacon::from_hex("400C592753572B3").unwrap();
+        assert!(beacon.is_location_protocol());
+        assert!(beacon.is_standard_location());
+        assert_eq!(beacon.country_code(), 512);
+        assert_eq!(beacon.location_protocol_code(), Some(0b0110));
+
+        let beacon = Beacon::from_hex("C00800000000000").unwrap();
+        assert!(beacon.is_user_protocol());
+        assert_eq!(beacon.country_code(), 512);
+        assert_eq!(beacon.location_protocol_code(), None);
+    }
+
+    #[test]
+    fn aux() {
+        let aux = |hex: &str| Beacon::from_hex(hex).unwrap().aux();
+
+        assert_eq!(aux("C009D6502815401"), Some(BeaconAux::Mmsi(512123456)));
+        assert_eq!(aux("40043C4800FFBFF"), Some(BeaconAux::Mmsi(512123456)));
+
+        // serial user, float-free EPIRB, serial 12345, type approval 123
+        assert_eq!(
+            aux("C00D40C0E4001EC"),
+            Some(BeaconAux::Serial {
+                serial_number: 12345,
+                type_approval: Some(123)
+            })
+        );
+        // serial user, aircraft address
+        assert_eq!(
+            aux("C00DB2048D00000"),
+            Some(BeaconAux::AircraftAddress(0xC81234))
+        );
+        // standard location EPIRB serial
+        assert_eq!(
+            aux("400C592753572B3"),
+            Some(BeaconAux::Serial {
+                serial_number: 5033,
+                type_approval: Some(178)
+            })
+        );
+
+        assert_eq!(aux("C00800000000000"), None);
+    }
+
+    #[test]
+    fn hex_id() {
+        let hex_id = |hex: &str| Beacon::from_hex(hex).unwrap().hex_id();
+        assert_eq!(hex_id("400C592753572B3"), "400C592752FFBFF");
+        assert_eq!(hex_id("400C000000FFBFF"), "400C000000FFBFF");
+        assert_eq!(hex_id("4014181CD4A15D7"), "4014181CBF81FE0");
+        assert_eq!(hex_id("c009d6502815401"), "C009D6502815401");
+    }
+
+    #[test]
+    fn standard_location_position() {
+        // S 43.5, E 172.75
+        let beacon = Beacon::from_hex("400C592753572B3").unwrap();
+        assert_eq!(beacon.position(), Some(Position::new(-43.5, 172.75)));
+
+        // default position
+        let beacon = Beacon::from_hex("400C000000FFBFF").unwrap();
+        assert_eq!(beacon.position(), None);
+    }
+
+    #[test]
+    fn national_location_position() {
+        // S 41 16', E 174 46'
+        let position = Beacon::from_hex("4014181CD4A15D7")
+            .unwrap()
+            .position()
+            .unwrap();
+        assert!((position.latitude - -(41.0 + 16.0 / 60.0)).abs() < 1e-9);
+        assert!((position.longitude - (174.0 + 46.0 / 60.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn protocol_version() {
+        for hex in ["400C592753572B3", "C009D6502815401", "40043C4800FFBFF"] {
+            assert_eq!(beacon_protocol_version(hex), Ok(1), "{}", hex);
+            assert!(!supports_second_generation(hex));
+        }
+
+        // country 512, protocol flag and code 101
+        assert_eq!(beacon_protocol_version("C01400000000000"), Ok(2));
+        assert!(supports_second_generation("C01400000000000"));
+
+        assert!(beacon_protocol_version("C0140000000000").is_err());
+        assert!(!supports_second_generation("C0140000000000Z"));
+    }
+
+    #[test]
+    fn user_protocol_has_no_position() {
+        let beacon = Beacon::from_hex("C00800000000000").unwrap();
+        assert_eq!(beacon.position(), None);
+    }
+
+    #[test]
+    fn mmsi() {
+        // maritime user protocol, MMSI 512123456
+        let beacon = Beacon::from_hex("C009D6502815401").unwrap();
+        assert_eq!(beacon.user_protocol_code(), Some(0b010));
+        assert_eq!(beacon.mmsi(), Some(512123456));
+
+        // standard location EPIRB-MMSI protocol, MMSI 512123456
+        let beacon = Beacon::from_hex("40043C4800FFBFF").unwrap();
+        assert_eq!(beacon.mmsi(), Some(512123456));
+
+        // standard location EPIRB serial protocol
+        assert_eq!(Beacon::from_hex("400C592753572B3").unwrap().mmsi(), None);
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert!(Beacon::from_hex("400C592753572B").is_err());
+        assert!(Beacon::from_hex("+00C592753572B3").is_err());
+        assert!(Beacon::from_hex("400C592753572BZ").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! MT packet checksum
+//!
+//! Both MT(1) and MT(6) packets end with a 4 character hex checksum (`YYYY`).
+//! See [`crate::mt_raw::compute_checksum`] for how it is calculated, or [`ChecksumBuilder`] to
+//! calculate it incrementally.
+
+use std::fmt;
+use std::ops::BitXor;
+
+/// A MT packet checksum, displayed as 4 uppercase hex characters.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::checksum::Checksum;
+/// assert_eq!(Checksum(0xf84b).to_string(), "F84B");
+/// assert_eq!(format!("{:x}", Checksum(0xf84b)), "f84b");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checksum(pub u16);
+
+impl From<u16> for Checksum {
+    fn from(value: u16) -> Self {
+        Checksum(value)
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04X}", self.0)
+    }
+}
+
+/// Formats all 4 hex characters, with leading zeros. The `#` flag, width and fill are honoured.
+impl fmt::LowerHex for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &format!("{:04x}", self.0))
+    }
+}
+
+/// Formats all 4 hex characters, with leading zeros. The `#` flag, width and fill are honoured.
+impl fmt::UpperHex for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &format!("{:04X}", self.0))
+    }
+}
+
+/// Formats all 16 bits, with leading zeros. The `#` flag, width and fill are honoured.
+impl fmt::Binary for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0b", &format!("{:016b}", self.0))
+    }
+}
+
+impl fmt::Octal for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Octal::fmt(&self.0, f)
+    }
+}
+
+impl BitXor for Checksum {
+    type Output = Checksum;
+
+    fn bitxor(self, rhs: Checksum) -> Checksum {
+        Checksum(self.0 ^ rhs.0)
+    }
+}
+
+/// Incremental checksum calculation, e.g. as bytes arrive from a stream.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::checksum::{Checksum, ChecksumBuilder};
+/// let mut builder = ChecksumBuilder::new();
+/// for byte in "FFFE2FA00E0000CBAB959DB0903788C71B79".bytes() {
+///     builder.update(byte);
+/// }
+/// assert_eq!(builder.finalize(), Checksum(0xf84b));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChecksumBuilder {
+    state: u16,
+}
+
+impl ChecksumBuilder {
+    /// Creates a builder with no byte processed yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes `byte`: XORs it into the checksum, then rotates the checksum left by one bit.
+    pub fn update(&mut self, byte: u8) {
+        self.state = (self.state ^ byte as u16).rotate_left(1);
+    }
+
+    /// Returns the checksum of the bytes processed so far.
+    pub fn finalize(self) -> Checksum {
+        Checksum(self.state)
+    }
+
+    /// Discards the bytes processed so far.
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!(format!("{}", Checksum(0xf84b)), "F84B");
+        assert_eq!(format!("{}", Checksum(0x00ab)), "00AB");
+        assert_eq!(format!("{:x}", Checksum(0xf84b)), "f84b");
+        assert_eq!(format!("{:X}", Checksum(0xf84b)), "F84B");
+        assert_eq!(format!("{:#06x}", Checksum(0x00ab)), "0x00ab");
+        assert_eq!(format!("{:x}", Checksum(0x00ab)), "00ab");
+        assert_eq!(format!("{:X}", Checksum(0x0abc)), "0ABC");
+        assert_eq!(format!("{:#X}", Checksum(0x0abc)), "0x0ABC");
+        assert_eq!(format!("{:>6X}", Checksum(0x0abc)), "  0ABC");
+        assert_eq!(Checksum::from(0x4706), Checksum(0x4706));
+
+        assert_eq!(format!("{:b}", Checksum(0xf84b)), "1111100001001011");
+        assert_eq!(format!("{:b}", Checksum(0x00ab)), "0000000010101011");
+        assert_eq!(format!("{:#b}", Checksum(0x00ab)), "0b0000000010101011");
+        assert_eq!(format!("{:>18b}", Checksum(0x00ab)), "  0000000010101011");
+        assert_eq!(
+            format!("{:#020b}", Checksum(0x00ab)),
+            "0b000000000010101011"
+        );
+        assert_eq!(format!("{:o}", Checksum(0xf84b)), "174113");
+        assert_eq!(format!("{:#o}", Checksum(0x00ab)), "0o253");
+    }
+
+    #[test]
+    fn xor() {
+        assert_eq!(Checksum(0xf84b) ^ Checksum(0xf84b), Checksum(0));
+        assert_eq!(Checksum(0xff00) ^ Checksum(0x0ff0), Checksum(0xf0f0));
+    }
+
+    #[test]
+    fn builder_matches_compute_checksum() {
+        let data = b"FFFE2FA00E0000CBAB959DB0903788C71B79";
+        assert_eq!(data.len(), 36);
+
+        let mut builder = ChecksumBuilder::new();
+        for &byte in data {
+            builder.update(byte);
+        }
+        assert_eq!(
+            builder.finalize(),
+            Checksum(crate::mt_raw::compute_checksum(data))
+        );
+
+        builder.reset();
+        assert_eq!(builder, ChecksumBuilder::new());
+        assert_eq!(builder.finalize(), Checksum(0));
+
+        // every byte value, so the high bit gets rotated around
+        let data: Vec<u8> = (0..=255).collect();
+        data.iter().for_each(|&byte| builder.update(byte));
+        assert_eq!(
+            builder.finalize(),
+            Checksum(crate::mt_raw::compute_checksum(&data))
+        );
+    }
+}
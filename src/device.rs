@@ -0,0 +1,169 @@
+//! MT-RX device IDs
+//!
+//! Both MT(1) and MT(6) packets carry the 3 character configurable ID of the MT-RX that received
+//! the alert (`UUU`), by default “001”.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::mt_structured::MtStructured;
+
+/// A MT-RX configurable ID, as found in MT packets.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw;
+/// let parsed = mt_raw::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+/// assert!(parsed.id == "001");
+/// assert!("001" == parsed.id);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DeviceId(pub String);
+
+impl DeviceId {
+    /// Returns the ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the zero-padded ID of number `n`, or `None` if above 999.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::device::DeviceId;
+    /// assert_eq!(DeviceId::from_u16(1), Some(DeviceId::from("001")));
+    /// assert_eq!(DeviceId::from_u16(1000), None);
+    /// ```
+    pub fn from_u16(n: u16) -> Option<DeviceId> {
+        (n <= 999).then(|| DeviceId(format!("{:03}", n)))
+    }
+
+    /// Returns the ID as a number, or `None` if it is not 3 decimal digits.
+    pub fn to_u16(&self) -> Option<u16> {
+        if self.0.len() != 3 || !self.0.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        self.0.parse().ok()
+    }
+
+    /// Returns the ID following this one, or `None` at 999 or if the ID is not numeric.
+    pub fn next(&self) -> Option<DeviceId> {
+        DeviceId::from_u16(self.to_u16()? + 1)
+    }
+}
+
+impl From<&str> for DeviceId {
+    fn from(value: &str) -> Self {
+        DeviceId(value.to_string())
+    }
+}
+
+impl From<String> for DeviceId {
+    fn from(value: String) -> Self {
+        DeviceId(value)
+    }
+}
+
+impl fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<&str> for DeviceId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for DeviceId {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<DeviceId> for &str {
+    fn eq(&self, other: &DeviceId) -> bool {
+        *self == other.0
+    }
+}
+
+/// Maps MT-RX IDs to site names, for installations with multiple receivers.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{device::SiteRegistry, mt_structured};
+/// let mut sites = SiteRegistry::default();
+/// sites.register("001", "Lyttelton");
+/// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+/// assert_eq!(sites.resolve(&parsed), Some("Lyttelton"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SiteRegistry {
+    sites: HashMap<DeviceId, String>,
+}
+
+impl SiteRegistry {
+    /// Associates the MT-RX `id` with the site `name`, replacing any previous name.
+    pub fn register(&mut self, id: &str, name: &str) {
+        self.sites.insert(DeviceId::from(id), name.to_string());
+    }
+
+    /// Returns the site name of the MT-RX that received `msg`, if registered.
+    pub fn resolve(&self, msg: &MtStructured) -> Option<&str> {
+        self.sites.get(&msg.id).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_with_strings() {
+        let id = DeviceId::from("001");
+        assert!(id == "001");
+        assert!("001" == id);
+        let owned = String::from("001");
+        assert!(id == owned);
+        assert!(id != "002");
+        assert!("002" != id);
+        assert_eq!(id.to_string(), "001");
+    }
+
+    #[test]
+    fn numeric() {
+        assert_eq!(DeviceId::from_u16(1), Some(DeviceId("001".to_string())));
+        assert_eq!(DeviceId::from_u16(999), Some(DeviceId("999".to_string())));
+        assert_eq!(DeviceId::from_u16(1000), None);
+        for n in [0, 1, 42, 999] {
+            assert_eq!(DeviceId::from_u16(n).unwrap().to_u16(), Some(n));
+        }
+
+        assert_eq!(DeviceId::from("01").to_u16(), None);
+        assert_eq!(DeviceId::from("+01").to_u16(), None);
+        assert_eq!(DeviceId::from("A01").to_u16(), None);
+
+        assert_eq!(DeviceId::from("009").next(), Some(DeviceId::from("010")));
+        assert_eq!(DeviceId::from("999").next(), None);
+        assert_eq!(DeviceId::from("A01").next(), None);
+    }
+
+    #[test]
+    fn site_registry() {
+        let mut sites = SiteRegistry::default();
+        sites.register("001", "Lyttelton");
+        sites.register("002", "Timaru");
+
+        let parsed =
+            crate::mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(sites.resolve(&parsed), Some("Lyttelton"));
+
+        sites.register("001", "Akaroa");
+        assert_eq!(sites.resolve(&parsed), Some("Akaroa"));
+
+        let parsed =
+            crate::mt_structured::parse("MT1003000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(sites.resolve(&parsed), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! Geohash geocoding
+//!
+//! Converts [`Position`]s to and from [geohashes](https://en.wikipedia.org/wiki/Geohash), a
+//! compact base-32 geocode where every extra character narrows down the encoded cell.
+
+use crate::position::Position;
+use crate::ParseError;
+
+/// Geohash base-32 alphabet.
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest supported geohash, about 4 cm wide.
+const MAX_PRECISION: usize = 12;
+
+impl Position {
+    /// Returns the geohash of the position, `precision` characters long (clamped to 1 to 12).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::position::Position;
+    /// assert_eq!(Position::new(57.64911, 10.40744).to_geohash(11), "u4pruydqqvj");
+    /// ```
+    pub fn to_geohash(&self, precision: usize) -> String {
+        let precision = precision.clamp(1, MAX_PRECISION);
+        let mut lat = (-90.0, 90.0);
+        let mut long = (-180.0, 180.0);
+        let mut hash = String::with_capacity(precision);
+
+        let mut even = true;
+        while hash.len() < precision {
+            let mut index = 0;
+            for _ in 0..5 {
+                let (range, value) = if even {
+                    (&mut long, self.longitude)
+                } else {
+                    (&mut lat, self.latitude)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                if value >= mid {
+                    index = (index << 1) | 1;
+                    range.0 = mid;
+                } else {
+                    index <<= 1;
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+            hash.push(BASE32[index] as char);
+        }
+        hash
+    }
+
+    /// Tries to decode a geohash (case insensitive, 1 to 12 characters), returning the center
+    /// of its cell.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::position::Position;
+    /// let position = Position::from_geohash("u4pruydqqvj").unwrap();
+    /// assert!((position.latitude - 57.64911).abs() < 1e-5);
+    /// assert!((position.longitude - 10.40744).abs() < 1e-5);
+    /// ```
+    pub fn from_geohash(hash: &str) -> Result<Position, ParseError> {
+        if hash.is_empty() || hash.len() > MAX_PRECISION {
+            return Err(ParseError::Invalid);
+        }
+
+        let mut lat = (-90.0, 90.0);
+        let mut long = (-180.0, 180.0);
+        let mut even = true;
+        for c in hash.bytes() {
+            let index = BASE32
+                .iter()
+                .position(|&b| b == c.to_ascii_lowercase())
+                .ok_or(ParseError::Invalid)?;
+            for bit in (0..5).rev() {
+                let range = if even { &mut long } else { &mut lat };
+                let mid = (range.0 + range.1) / 2.0;
+                if index >> bit & 1 == 1 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+        }
+
+        Ok(Position::new(
+            (lat.0 + lat.1) / 2.0,
+            (long.0 + long.1) / 2.0,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_position() {
+        // MT1 sample, 43°32'12"S 172°37'56"E
+        let position = Position::new(-43.536667, 172.632222);
+        assert_eq!(position.to_geohash(12), "rb6b266uw349");
+        assert_eq!(position.to_geohash(5), "rb6b2");
+        assert_eq!(position.to_geohash(0), "r");
+        assert_eq!(position.to_geohash(20).len(), 12);
+
+        for precision in 1..=12 {
+            let decoded = Position::from_geohash(&position.to_geohash(precision)).unwrap();
+            // half the cell size, longitude gets the extra bit on odd bit counts
+            let bits = 5 * precision as i32;
+            let lat_error = 90.0 / 2f64.powi(bits / 2);
+            let long_error = 180.0 / 2f64.powi(bits - bits / 2);
+            assert!((decoded.latitude - position.latitude).abs() <= lat_error);
+            assert!((decoded.longitude - position.longitude).abs() <= long_error);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Position::from_geohash("").is_err());
+        assert!(Position::from_geohash("rb6a").is_err());
+        assert!(Position::from_geohash("rb6b266uw3490").is_err());
+        assert_eq!(
+            Position::from_geohash("RB6B2"),
+            Position::from_geohash("rb6b2")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! GPX (GPS Exchange Format) export
+//!
+//! Converts located [`MtStructured`] messages to GPX waypoints, e.g. to load alerts into a GPS
+//! device or mapping software.
+
+use crate::mt_structured::MtStructured;
+
+impl MtStructured {
+    /// Returns the location as a GPX waypoint (`<wpt>`), named after the beacon hex code and
+    /// described by the MT-RX ID, sequence number and message type. Returns `None` if the
+    /// location is not available (see [`MtStructured::position`]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(
+    ///     parsed.to_gpx_waypoint().unwrap(),
+    ///     r#"<wpt lat="-43.536667" lon="172.632222"><name>400C592753572B3</name><desc>001 000 A</desc></wpt>"#
+    /// );
+    /// ```
+    pub fn to_gpx_waypoint(&self) -> Option<String> {
+        let position = self.position()?;
+        Some(format!(
+            r#"<wpt lat="{:.6}" lon="{:.6}"><name>{}</name><desc>{} {:03} {}</desc></wpt>"#,
+            position.latitude,
+            position.longitude,
+            escape(&self.beacon),
+            escape(self.id.as_str()),
+            self.sequence_number,
+            self.message_type.as_char(),
+        ))
+    }
+}
+
+/// Returns a complete GPX 1.1 document with a waypoint for every located message in `messages`
+/// (see [`MtStructured::to_gpx_waypoint`]).
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{gpx, mt_structured};
+/// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+/// let gpx = gpx::to_gpx_file(&[parsed]);
+/// assert!(gpx.starts_with("<?xml"));
+/// assert!(gpx.contains("<wpt "));
+/// ```
+pub fn to_gpx_file(messages: &[MtStructured]) -> String {
+    let mut gpx = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<gpx version=\"1.1\" creator=\"wte-mt-rx-parser\" ",
+        "xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    ));
+    for waypoint in messages.iter().filter_map(MtStructured::to_gpx_waypoint) {
+        gpx.push_str("  ");
+        gpx.push_str(&waypoint);
+        gpx.push('\n');
+    }
+    gpx.push_str("</gpx>\n");
+    gpx
+}
+
+/// Escapes the XML special characters of `s`.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_structured::parse;
+
+    #[test]
+    fn waypoint() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let waypoint = parsed.to_gpx_waypoint().unwrap();
+        assert!(waypoint.starts_with(r#"<wpt lat="-43.536667" lon="172.632222">"#));
+
+        let parsed = parse("MT1001000AL400C592753572B323---------------4706").unwrap();
+        assert_eq!(parsed.to_gpx_waypoint(), None);
+    }
+
+    #[test]
+    fn file() {
+        let located = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let unlocated = parse("MT1001001AL400C592753572B323---------------4706").unwrap();
+        let gpx = to_gpx_file(&[located.clone(), unlocated, located]);
+        assert_eq!(gpx.matches("<wpt ").count(), 2);
+        assert!(gpx.ends_with("</gpx>\n"));
+
+        assert_eq!(to_gpx_file(&[]).lines().count(), 3);
+    }
+
+    #[test]
+    fn escaping() {
+        let mut parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        parsed.id = "<&>".into();
+        assert!(parsed
+            .to_gpx_waypoint()
+            .unwrap()
+            .contains("<desc>&lt;&amp;&gt; 000 A</desc>"));
+    }
+}
@@ -0,0 +1,104 @@
+//! Per-device message history
+//!
+//! [`MessageHistory`] keeps the last N messages received from each MT-RX (by configurable ID),
+//! dropping the oldest ones, so long-running processes do not accumulate unbounded history.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::ParsedMessage;
+
+/// Bounded history of the last messages per MT-RX ID.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MessageHistory {
+    /// Maximum number of messages kept per device.
+    capacity: usize,
+
+    /// Messages per device ID, oldest first.
+    inner: HashMap<String, VecDeque<ParsedMessage>>,
+}
+
+impl MessageHistory {
+    /// Creates a history keeping at most `capacity` messages per device.
+    pub fn new(capacity: usize) -> Self {
+        MessageHistory {
+            capacity,
+            inner: HashMap::new(),
+        }
+    }
+
+    /// Records `msg`, dropping the oldest message of its device if over capacity.
+    ///
+    /// Messages without a device ID (see [`ParsedMessage::device_id`]) are not recorded.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::history::MessageHistory;
+    /// let mut history = MessageHistory::new(3);
+    /// history.push(wte_mt_rx_parser::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap());
+    /// assert!(history.last_for("001").is_some());
+    /// ```
+    pub fn push(&mut self, msg: ParsedMessage) {
+        let Some(id) = msg.device_id() else {
+            return;
+        };
+
+        let messages = self.inner.entry(id.to_string()).or_default();
+        messages.push_back(msg);
+        while messages.len() > self.capacity {
+            messages.pop_front();
+        }
+    }
+
+    /// Returns the recorded messages of `device_id`, oldest first.
+    pub fn messages_for(&self, device_id: &str) -> impl Iterator<Item = &ParsedMessage> {
+        self.inner.get(device_id).into_iter().flatten()
+    }
+
+    /// Returns the most recent message of `device_id`.
+    pub fn last_for(&self, device_id: &str) -> Option<&ParsedMessage> {
+        self.inner.get(device_id)?.back()
+    }
+
+    /// Removes every recorded message.
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn capacity_per_device() {
+        let mut history = MessageHistory::new(3);
+        for seq in 0..10 {
+            let first = format!("MT6001{:03}FFFE2FA00E0000CBAB959DB0903788C71B79F84B", seq);
+            let second = format!("MT6002{:03}FFFE2FA00E0000CBAB959DB0903788C71B79F84B", seq);
+            history.push(parse(&first).unwrap());
+            history.push(parse(&second).unwrap());
+        }
+        history.push(parse("SS,A,123").unwrap());
+
+        for id in ["001", "002"] {
+            let sequences: Vec<_> = history
+                .messages_for(id)
+                .map(|msg| match msg {
+                    ParsedMessage::MtRaw(raw) => raw.sequence_number,
+                    _ => unreachable!(),
+                })
+                .collect();
+            assert_eq!(sequences, vec![7, 8, 9]);
+        }
+        assert_eq!(
+            history.last_for("001"),
+            Some(&parse("MT6001009FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap())
+        );
+        assert_eq!(history.messages_for("003").count(), 0);
+        assert_eq!(history.last_for("003"), None);
+
+        history.clear();
+        assert_eq!(history.messages_for("001").count(), 0);
+    }
+}
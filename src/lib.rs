@@ -2,16 +2,39 @@
 //!
 //! Please refer to [MT-RX-3 User Manual](https://www.wte.co.nz/uploads/9/9/8/6/99862766/mt-rx-3_406_epirb_receiver-manual_v2-62.pdf) for more information.
 
+use std::collections::HashMap;
 use std::num::ParseIntError;
+use std::str::Utf8Error;
 
 use mt_raw::MtRaw;
 use mt_structured::MtStructured;
+use parser::Parser;
+use position::Position;
 use rss::Rss;
 use thiserror::Error;
 
+pub mod alert;
+pub mod batch;
+pub mod beacon;
+pub mod checksum;
+pub mod device;
+pub mod geohash;
+pub mod gpx;
+pub mod history;
+pub mod metrics;
 pub mod mt_raw;
 pub mod mt_structured;
+pub mod parser;
+pub mod pipeline;
+pub mod position;
 pub mod rss;
+pub mod sequence;
+pub mod sink;
+pub mod stream;
+pub mod suppressor;
+pub mod timestamped;
+pub mod track;
+pub mod wkt;
 
 /// Represents an error when parsing a message went wrong.
 #[derive(Error, Clone, Debug, PartialEq)]
@@ -24,6 +47,37 @@ pub enum ParseError {
 
     #[error("invalid sentence, not parsable")]
     Invalid,
+
+    #[error("invalid RSS type (expected 'A' or '1', found {found:?})")]
+    InvalidRssType { found: char },
+
+    #[error("RSS value out of range (expected 0 to 255, found {found})")]
+    RssOutOfRange { found: u16 },
+
+    #[error("invalid {field} field (found {raw:?})")]
+    InvalidField { field: &'static str, raw: String },
+
+    #[error("direction on the wrong axis (latitude {lat:?}, longitude {long:?})")]
+    DirectionAxisMismatch { lat: char, long: char },
+
+    #[error("line exceeds {max} bytes without a terminator")]
+    LineTooLong { max: usize },
+
+    #[error("message contains non-ASCII characters")]
+    NonAscii,
+
+    #[error("invalid UTF-8 data")]
+    Utf8Error(#[from] Utf8Error),
+
+    #[error("failed to read input ({kind})")]
+    Io { kind: std::io::ErrorKind },
+}
+
+/// Represents an error when building a message went wrong.
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum BuildError {
+    #[error("invalid MT-RX ID (expected 3 ASCII characters, found {found:?})")]
+    InvalidId { found: String },
 }
 
 /// Represents the parsed message.
@@ -38,13 +92,222 @@ pub enum ParsedMessage {
     /// MT Raw Data Serial Out Packet Format message.
     MtRaw(MtRaw),
 
-    /// Invalid message.
-    Invalid,
+    /// Invalid (unrecognized) message, holding the original input.
+    ///
+    /// This includes any MT-RX status or heartbeat line, whose format is not documented in the
+    /// MT-RX manual and is therefore not parsed.
+    Invalid(String),
+}
+
+/// Represents the kind of a [`ParsedMessage`], without its contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    /// See [`ParsedMessage::Rss`].
+    Rss,
+
+    /// See [`ParsedMessage::MtStructured`].
+    MtStructured,
+
+    /// See [`ParsedMessage::MtRaw`].
+    MtRaw,
+
+    /// Unrecognized message, see [`ParsedMessage::Invalid`].
+    Unknown,
+}
+
+impl ParsedMessage {
+    /// Returns the [`MessageKind`] of this message.
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            ParsedMessage::Rss(_) => MessageKind::Rss,
+            ParsedMessage::MtStructured(_) => MessageKind::MtStructured,
+            ParsedMessage::MtRaw(_) => MessageKind::MtRaw,
+            ParsedMessage::Invalid(_) => MessageKind::Unknown,
+        }
+    }
+
+    /// Returns the MT-RX configurable ID of MT messages, or `None` for [`ParsedMessage::Rss`]
+    /// and [`ParsedMessage::Invalid`].
+    pub fn device_id(&self) -> Option<&str> {
+        match self {
+            ParsedMessage::MtStructured(msg) => Some(msg.id.as_str()),
+            ParsedMessage::MtRaw(msg) => Some(msg.id.as_str()),
+            ParsedMessage::Rss(_) | ParsedMessage::Invalid(_) => None,
+        }
+    }
+
+    /// Returns the message in its wire format, or `None` for [`ParsedMessage::Invalid`].
+    ///
+    /// ## Examples
+    /// ```
+    /// let message = "MT1001000AL400C592753572B323433212S1723756E4706";
+    /// let parsed = wte_mt_rx_parser::parse(message).unwrap();
+    /// assert_eq!(parsed.encode().unwrap(), message);
+    /// ```
+    pub fn encode(&self) -> Option<String> {
+        match self {
+            ParsedMessage::Rss(msg) => Some(msg.encode()),
+            ParsedMessage::MtStructured(msg) => Some(msg.encode()),
+            ParsedMessage::MtRaw(msg) => Some(msg.encode()),
+            ParsedMessage::Invalid(_) => None,
+        }
+    }
+
+    /// Returns the message as a sentence: its wire format (see [`ParsedMessage::encode`]), or the
+    /// original input for [`ParsedMessage::Invalid`], so every message can be passed through.
+    ///
+    /// ## Examples
+    /// ```
+    /// let parsed = wte_mt_rx_parser::parse("garbage").unwrap();
+    /// assert_eq!(parsed.to_raw_sentence(), "garbage");
+    /// ```
+    pub fn to_raw_sentence(&self) -> String {
+        match self {
+            ParsedMessage::Invalid(raw) => raw.clone(),
+            msg => msg.encode().unwrap_or_default(),
+        }
+    }
+
+    /// Returns the message wrapped in a NMEA style envelope (`$<message>*HH<CR><LF>`), where `HH`
+    /// is the NMEA checksum (XOR of every byte between `$` and `*`).
+    /// Returns `None` for [`ParsedMessage::Invalid`].
+    ///
+    /// ## Examples
+    /// ```
+    /// let parsed = wte_mt_rx_parser::parse("SS,A,123").unwrap();
+    /// assert_eq!(parsed.to_nmea().unwrap(), "$SS,A,123*71\r\n");
+    /// ```
+    pub fn to_nmea(&self) -> Option<String> {
+        Some(nmea_sentence(&self.encode()?))
+    }
+
+    /// Returns the location carried by the message, if available.
+    ///
+    /// The location of [`ParsedMessage::MtRaw`] is decoded from the raw data
+    /// (see [`MtRaw::into_mt_structured_via_decode`]). [`ParsedMessage::Rss`] and
+    /// [`ParsedMessage::Invalid`] have no location.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            ParsedMessage::MtStructured(msg) => msg.position(),
+            ParsedMessage::MtRaw(msg) => msg.into_mt_structured_via_decode().ok()??.position(),
+            ParsedMessage::Rss(_) | ParsedMessage::Invalid(_) => None,
+        }
+    }
+
+    /// Returns the beacon hex code carried by the message, if available.
+    ///
+    /// The beacon hex code of [`ParsedMessage::MtRaw`] is decoded from the raw data, as for
+    /// [`ParsedMessage::position`]. Use [`beacon::Beacon::hex_id`] for the 15 Hex ID.
+    ///
+    /// ## Examples
+    /// ```
+    /// let parsed = wte_mt_rx_parser::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+    /// assert_eq!(parsed.beacon_hex().unwrap(), "401C000197572B3");
+    /// ```
+    pub fn beacon_hex(&self) -> Option<String> {
+        match self {
+            ParsedMessage::MtStructured(msg) => Some(msg.beacon.clone()),
+            ParsedMessage::MtRaw(MtRaw {
+                decoded: Some(decoded),
+                ..
+            }) => Some(decoded.beacon.clone()),
+            ParsedMessage::MtRaw(msg) => Some(msg.into_mt_structured_via_decode().ok()??.beacon),
+            ParsedMessage::Rss(_) | ParsedMessage::Invalid(_) => None,
+        }
+    }
+
+    /// Returns a score between `0.0` and `1.0` of how likely it is that the message was
+    /// received without corruption.
+    ///
+    /// Checksum validity weighs half of the score. For [`ParsedMessage::MtStructured`], direction
+    /// validity and coordinate ranges weigh a quarter each. [`ParsedMessage::Rss`] carries no
+    /// integrity information and always scores `1.0`, while [`ParsedMessage::Invalid`] scores `0.0`.
+    ///
+    /// ## Examples
+    /// ```
+    /// let parsed = wte_mt_rx_parser::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+    /// assert_eq!(parsed.confidence(), 1.0);
+    /// ```
+    pub fn confidence(&self) -> f32 {
+        let score = |valid: bool, weight: f32| if valid { weight } else { 0.0 };
+        match self {
+            ParsedMessage::Rss(_) => 1.0,
+            ParsedMessage::MtStructured(msg) => {
+                score(msg.verify_checksum(), 0.5)
+                    + score(msg.directions_are_valid(), 0.25)
+                    + score(msg.coordinates_in_range(), 0.25)
+            }
+            ParsedMessage::MtRaw(msg) => score(msg.verify_checksum(), 0.5) + 0.5,
+            ParsedMessage::Invalid(_) => 0.0,
+        }
+    }
+}
+
+impl From<Rss> for ParsedMessage {
+    fn from(msg: Rss) -> Self {
+        ParsedMessage::Rss(msg)
+    }
+}
+
+impl From<MtStructured> for ParsedMessage {
+    fn from(msg: MtStructured) -> Self {
+        ParsedMessage::MtStructured(msg)
+    }
+}
+
+impl From<MtRaw> for ParsedMessage {
+    fn from(msg: MtRaw) -> Self {
+        ParsedMessage::MtRaw(msg)
+    }
+}
+
+/// Gives the original message back if it is not a [`ParsedMessage::Rss`].
+impl TryFrom<ParsedMessage> for Rss {
+    type Error = ParsedMessage;
+
+    fn try_from(msg: ParsedMessage) -> Result<Self, Self::Error> {
+        match msg {
+            ParsedMessage::Rss(v) => Ok(v),
+            other => Err(other),
+        }
+    }
+}
+
+/// Gives the original message back if it is not a [`ParsedMessage::MtStructured`].
+impl TryFrom<ParsedMessage> for MtStructured {
+    type Error = ParsedMessage;
+
+    fn try_from(msg: ParsedMessage) -> Result<Self, Self::Error> {
+        match msg {
+            ParsedMessage::MtStructured(v) => Ok(v),
+            other => Err(other),
+        }
+    }
+}
+
+/// Gives the original message back if it is not a [`ParsedMessage::MtRaw`].
+impl TryFrom<ParsedMessage> for MtRaw {
+    type Error = ParsedMessage;
+
+    fn try_from(msg: ParsedMessage) -> Result<Self, Self::Error> {
+        match msg {
+            ParsedMessage::MtRaw(v) => Ok(v),
+            other => Err(other),
+        }
+    }
+}
+
+/// Wraps `body` in a NMEA envelope (`$<body>*HH<CR><LF>`).
+pub(crate) fn nmea_sentence(body: &str) -> String {
+    let checksum = body.bytes().fold(0, |acc, b| acc ^ b);
+    format!("${}*{:02X}\r\n", body, checksum)
 }
 
 /// Tries to parse `message` into one of [`ParsedMessage`] types.
 /// Returns [`ParsedMessage::Invalid`] if it's an invalid message, or [`ParseError`] if parsing went wrong.
 ///
+/// Use [`Parser`] for a configurable parser.
+///
 /// ## Examples
 /// ```
 /// let samples = vec![
@@ -58,20 +321,523 @@ pub enum ParsedMessage {
 ///     println!("{:?}", wte_mt_rx_parser::parse(s));
 /// }
 /// ```
+///
+/// The result must be used, discarding it is denied here:
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// wte_mt_rx_parser::parse("SS,A,123");
+/// ```
+#[must_use = "this returns the parse result without handling it"]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(message), err(level = "warn"))
+)]
 pub fn parse(message: &str) -> Result<ParsedMessage, ParseError> {
-    let parsed = match message.trim() {
-        msg if rss::is_rss(msg) => ParsedMessage::Rss(rss::parse(msg)?),
-        msg if mt_structured::is_mt(msg) => ParsedMessage::MtStructured(mt_structured::parse(msg)?),
-        msg if mt_raw::is_mt(msg) => ParsedMessage::MtRaw(mt_raw::parse(msg)?),
-        _ => ParsedMessage::Invalid,
-    };
+    let parsed = Parser::new().parse(message)?;
+    #[cfg(feature = "tracing")]
+    trace_parsed(&parsed);
     Ok(parsed)
 }
 
+/// Records a parsed message, warning if its checksum does not match.
+#[cfg(feature = "tracing")]
+fn trace_parsed(msg: &ParsedMessage) {
+    let checksum_ok = match msg {
+        ParsedMessage::MtStructured(msg) => msg.verify_checksum(),
+        ParsedMessage::MtRaw(msg) => msg.verify_checksum(),
+        ParsedMessage::Rss(_) | ParsedMessage::Invalid(_) => true,
+    };
+    if checksum_ok {
+        tracing::debug!(kind = ?msg.kind(), "parsed message");
+    } else {
+        tracing::warn!(kind = ?msg.kind(), "checksum mismatch");
+    }
+}
+
+/// Returns the kind of `message` from its prefix (`SS,`, `MT1` or `MT6`) only, without
+/// validating its length or fields. Leading whitespace is ignored, as in [`parse`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{classify, MessageKind};
+/// assert_eq!(classify("MT1001"), MessageKind::MtStructured);
+/// assert_eq!(classify("garbage"), MessageKind::Unknown);
+/// ```
+pub fn classify(message: &str) -> MessageKind {
+    detect(message.trim_start())
+}
+
+/// Returns the kind of `message` from its prefix, or [`MessageKind::Unknown`] if it is not
+/// recognized. Unlike [`classify`], leading whitespace is not ignored.
+///
+/// This is equivalent to checking [`rss::is_rss`], [`mt_structured::is_mt`] and
+/// [`mt_raw::is_mt`] in turn, with a single prefix comparison.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{detect, MessageKind};
+/// assert_eq!(detect("SS,A,123"), MessageKind::Rss);
+/// assert_eq!(detect("MT2001"), MessageKind::Unknown);
+/// ```
+pub fn detect(message: &str) -> MessageKind {
+    match message.get(..3) {
+        Some("SS,") => MessageKind::Rss,
+        Some("MT1") => MessageKind::MtStructured,
+        Some("MT6") => MessageKind::MtRaw,
+        _ => MessageKind::Unknown,
+    }
+}
+
+/// Parses `message` (see [`parse`]) only if `predicate` accepts its kind (see [`classify`]),
+/// returning `None` without parsing otherwise.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{parse_only_if, MessageKind};
+/// let only_mt1 = |kind| kind == MessageKind::MtStructured;
+/// assert!(parse_only_if("SS,A,123", only_mt1).is_none());
+/// ```
+pub fn parse_only_if(
+    message: &str,
+    predicate: impl Fn(MessageKind) -> bool,
+) -> Option<Result<ParsedMessage, ParseError>> {
+    predicate(classify(message)).then(|| parse(message))
+}
+
+/// Tries to parse `message` (see [`parse`]), returning `default` if parsing fails or the
+/// message is [`ParsedMessage::Invalid`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{parse_with_fallback, ParsedMessage};
+/// let sentinel = ParsedMessage::Invalid(String::new());
+/// assert_eq!(parse_with_fallback("garbage", sentinel.clone()), sentinel);
+/// ```
+pub fn parse_with_fallback(message: &str, default: ParsedMessage) -> ParsedMessage {
+    match parse(message) {
+        Ok(ParsedMessage::Invalid(_)) | Err(_) => default,
+        Ok(msg) => msg,
+    }
+}
+
+/// Parses every line of `input`, split on CR and/or LF. Empty lines are skipped.
+///
+/// ## Examples
+/// ```
+/// let results = wte_mt_rx_parser::parse_all("SS,A,123\r\nSS,1,123\r\n");
+/// assert_eq!(results.len(), 2);
+/// ```
+pub fn parse_all(input: &str) -> Vec<Result<ParsedMessage, ParseError>> {
+    input
+        .split(['\r', '\n'])
+        .filter(|line| !line.trim().is_empty())
+        .map(parse)
+        .collect()
+}
+
+/// Parses every line of the byte buffer `buf`, see [`parse_all`].
+/// Returns a single [`ParseError::Utf8Error`] if `buf` is not valid UTF-8.
+///
+/// ## Examples
+/// ```
+/// let results = wte_mt_rx_parser::parse_many_bytes(b"SS,A,123\r\nSS,1,123\r\n");
+/// assert_eq!(results.len(), 2);
+/// ```
+pub fn parse_many_bytes(buf: &[u8]) -> Vec<Result<ParsedMessage, ParseError>> {
+    match std::str::from_utf8(buf) {
+        Ok(input) => parse_all(input),
+        Err(err) => vec![Err(err.into())],
+    }
+}
+
+/// Tries to parse a null-terminated message `s`, e.g. received from a C serial library
+/// (see [`parse`]). Returns [`ParseError::Utf8Error`] if `s` is not valid UTF-8.
+///
+/// ## Examples
+/// ```
+/// let message = std::ffi::CString::new("SS,A,123").unwrap();
+/// assert!(wte_mt_rx_parser::parse_cstr(&message).is_ok());
+/// ```
+#[must_use = "this returns the parse result without handling it"]
+pub fn parse_cstr(s: &std::ffi::CStr) -> Result<ParsedMessage, ParseError> {
+    parse(s.to_str()?)
+}
+
+/// Parses every message of `messages`, separating the successfully parsed ones (including
+/// [`ParsedMessage::Invalid`]) from the errors, which are paired with the index of their message.
+///
+/// ## Examples
+/// ```
+/// let (messages, errors) = wte_mt_rx_parser::parse_resilient(&["SS,A,123", "SS,X,123"]);
+/// assert_eq!(messages.len(), 1);
+/// assert_eq!(errors[0].0, 1);
+/// ```
+pub fn parse_resilient(messages: &[&str]) -> (Vec<ParsedMessage>, Vec<(usize, ParseError)>) {
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+    for (i, message) in messages.iter().enumerate() {
+        match parse(message) {
+            Ok(msg) => parsed.push(msg),
+            Err(err) => errors.push((i, err)),
+        }
+    }
+    (parsed, errors)
+}
+
+/// Tries to parse `message` (see [`parse`]), returning `tag` alongside the result, e.g. to keep
+/// a request ID or source port paired with it.
+///
+/// ## Examples
+/// ```
+/// let (port, parsed) = wte_mt_rx_parser::parse_tagged("SS,A,123", 4001);
+/// assert_eq!(port, 4001);
+/// assert!(parsed.is_ok());
+/// ```
+pub fn parse_tagged<T>(message: &str, tag: T) -> (T, Result<ParsedMessage, ParseError>) {
+    (tag, parse(message))
+}
+
+/// Parses every message of `messages` with [`parse_tagged`], in order.
+///
+/// ## Examples
+/// ```
+/// let results = wte_mt_rx_parser::parse_tagged_iter(&[("SS,A,123", "a"), ("SS,X,123", "b")]);
+/// assert_eq!(results[1].0, "b");
+/// assert!(results[1].1.is_err());
+/// ```
+pub fn parse_tagged_iter<T: Clone>(
+    messages: &[(&str, T)],
+) -> Vec<(T, Result<ParsedMessage, ParseError>)> {
+    messages
+        .iter()
+        .map(|(message, tag)| parse_tagged(message, tag.clone()))
+        .collect()
+}
+
+/// Key of [`parse_multiple_receivers`] grouping messages without a device ID.
+pub const OTHER_RECEIVERS: &str = "__other__";
+
+/// Parses every message of `messages`, grouping them by the ID of the MT-RX that sent them
+/// (see [`ParsedMessage::device_id`]), in order.
+///
+/// Messages without a device ID ([`ParsedMessage::Rss`] and [`ParsedMessage::Invalid`]) are
+/// grouped under [`OTHER_RECEIVERS`]. Messages that fail to parse are left out, use
+/// [`parse_resilient`] to inspect them.
+///
+/// ## Examples
+/// ```
+/// let messages = ["MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B", "SS,A,123"];
+/// let receivers = wte_mt_rx_parser::parse_multiple_receivers(&messages);
+/// assert_eq!(receivers["001"].len(), 1);
+/// assert_eq!(receivers[wte_mt_rx_parser::OTHER_RECEIVERS].len(), 1);
+/// ```
+pub fn parse_multiple_receivers(messages: &[&str]) -> HashMap<String, Vec<ParsedMessage>> {
+    let mut receivers: HashMap<String, Vec<ParsedMessage>> = HashMap::new();
+    for msg in messages.iter().filter_map(|m| parse(m).ok()) {
+        let id = msg.device_id().unwrap_or(OTHER_RECEIVERS).to_string();
+        receivers.entry(id).or_default().push(msg);
+    }
+    receivers
+}
+
+/// Returns the sorted, unique IDs of the MT-RX units that sent `messages`.
+///
+/// ## Examples
+/// ```
+/// let messages = ["MT6002001FFFE2FA00E0000CBAB959DB0903788C71B79F84B", "SS,A,123"];
+/// assert_eq!(wte_mt_rx_parser::receivers_seen(&messages), vec!["002"]);
+/// ```
+pub fn receivers_seen(messages: &[&str]) -> Vec<String> {
+    let mut ids: Vec<String> = messages
+        .iter()
+        .filter_map(|m| parse(m).ok())
+        .filter_map(|msg| msg.device_id().map(str::to_string))
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Verifies the checksum of every MT(1) and MT(6) message of `messages`, returning
+/// `(index, is_valid)` pairs in order.
+///
+/// [`ParsedMessage::Rss`] and [`ParsedMessage::Invalid`] messages carry no checksum and are
+/// skipped, so they have no entry in the result.
+///
+/// ## Examples
+/// ```
+/// let messages: Vec<_> = ["SS,A,123", "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B"]
+///     .into_iter()
+///     .map(|m| wte_mt_rx_parser::parse(m).unwrap())
+///     .collect();
+/// assert_eq!(wte_mt_rx_parser::verify_all_checksums(&messages), vec![(1, true)]);
+/// ```
+pub fn verify_all_checksums(messages: &[ParsedMessage]) -> Vec<(usize, bool)> {
+    messages
+        .iter()
+        .enumerate()
+        .filter_map(|(i, msg)| match msg {
+            ParsedMessage::MtStructured(msg) => Some((i, msg.verify_checksum())),
+            ParsedMessage::MtRaw(msg) => Some((i, msg.verify_checksum())),
+            ParsedMessage::Rss(_) | ParsedMessage::Invalid(_) => None,
+        })
+        .collect()
+}
+
+/// Keeps only the successfully parsed messages of `results`, dropping any [`ParseError`]
+/// and [`ParsedMessage::Invalid`] entries.
+///
+/// ## Examples
+/// ```
+/// let results = vec!["SS,A,123", "SS,X,123", "garbage"]
+///     .into_iter()
+///     .map(wte_mt_rx_parser::parse)
+///     .collect();
+/// assert_eq!(wte_mt_rx_parser::retain_valid(results).len(), 1);
+/// ```
+pub fn retain_valid(results: Vec<Result<ParsedMessage, ParseError>>) -> Vec<ParsedMessage> {
+    results
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|msg| !matches!(msg, ParsedMessage::Invalid(_)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::error::Error;
+
     use super::*;
 
+    #[test]
+    fn error_source() {
+        let inner = "12a".parse::<u8>().unwrap_err();
+        let err = ParseError::from(inner.clone());
+        let source = err.source().unwrap();
+        assert_eq!(source.downcast_ref::<ParseIntError>(), Some(&inner));
+
+        let err = ParseError::SizeNotMatch {
+            expected: 8,
+            found: 7,
+        };
+        assert!(err.source().is_none());
+        assert!(ParseError::Invalid.source().is_none());
+    }
+
+    #[test]
+    fn parse_failure_source() {
+        // sequence number is not a number
+        let err = parse("MT6001X01FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap_err();
+        assert!(err.source().is_some_and(|e| e.is::<ParseIntError>()));
+
+        let err = parse_many_bytes(b"SS,A,\xff23").remove(0).unwrap_err();
+        assert!(err.source().is_some_and(|e| e.is::<Utf8Error>()));
+    }
+
+    #[test]
+    fn resilient() {
+        let (messages, errors) = parse_resilient(&[
+            "SS,A,123",
+            "SS,X,123",
+            "garbage",
+            "MT1001000AL400C592753572B323433212S1723756E470",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+        ]);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1], ParsedMessage::Invalid("garbage".to_string()));
+        assert_eq!(
+            errors,
+            vec![
+                (1, ParseError::InvalidRssType { found: 'X' }),
+                (
+                    3,
+                    ParseError::SizeNotMatch {
+                        expected: 47,
+                        found: 46
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_receivers() {
+        let messages = [
+            "MT1002000AL400C592753572B323433212S1723756E4706",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+            "SS,A,123",
+            "MT6002001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+        ];
+
+        let receivers = parse_multiple_receivers(&messages);
+        assert_eq!(receivers.len(), 3);
+        assert_eq!(
+            receivers["002"],
+            vec![parse(messages[0]).unwrap(), parse(messages[3]).unwrap()]
+        );
+        assert_eq!(receivers["001"], vec![parse(messages[1]).unwrap()]);
+        assert_eq!(
+            receivers[OTHER_RECEIVERS],
+            vec![parse(messages[2]).unwrap()]
+        );
+
+        assert_eq!(receivers_seen(&messages), vec!["001", "002"]);
+    }
+
+    #[test]
+    fn beacon_hex() {
+        let mt1 = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(mt1.beacon_hex().unwrap(), "400C592753572B3");
+
+        let message = "MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131";
+        assert_eq!(parse(message).unwrap().beacon_hex(), mt1.beacon_hex());
+        let decoded = Parser::new().decode_raw(true).parse(message).unwrap();
+        assert_eq!(decoded.beacon_hex(), mt1.beacon_hex());
+
+        assert_eq!(parse("SS,A,123").unwrap().beacon_hex(), None);
+        assert_eq!(ParsedMessage::Invalid("garbage".into()).beacon_hex(), None);
+    }
+
+    #[test]
+    fn tagged() {
+        let (tag, parsed) = parse_tagged("SS,A,123", 7);
+        assert_eq!(tag, 7);
+        assert_eq!(parsed, parse("SS,A,123"));
+
+        let (tag, parsed) = parse_tagged("SS,X,123", "failing");
+        assert_eq!(tag, "failing");
+        assert_eq!(parsed, Err(ParseError::InvalidRssType { found: 'X' }));
+
+        let results = parse_tagged_iter(&[
+            ("SS,1,000", 1),
+            ("SS,A,999", 2),
+            ("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B", 3),
+        ]);
+        let tags: Vec<_> = results.iter().map(|(tag, _)| *tag).collect();
+        assert_eq!(tags, vec![1, 2, 3]);
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].1, Err(ParseError::RssOutOfRange { found: 999 }));
+        assert!(results[2].1.is_ok());
+        assert!(parse_tagged_iter::<u8>(&[]).is_empty());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn tracing_events() {
+        assert!(parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").is_ok());
+        assert!(logs_contain("DEBUG"));
+        assert!(logs_contain("parsed message"));
+        assert!(!logs_contain("WARN"));
+
+        assert!(parse("SS,X,123").is_err());
+        assert!(logs_contain("WARN"));
+        assert!(logs_contain("invalid RSS type"));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn tracing_checksum_mismatch() {
+        assert!(parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B790000").is_ok());
+        assert!(logs_contain("WARN"));
+        assert!(logs_contain("checksum mismatch"));
+    }
+
+    #[test]
+    fn parse_only_matching() {
+        let only_mt1 = |kind| kind == MessageKind::MtStructured;
+
+        let message = "MT1001000AL400C592753572B323433212S1723756E4706";
+        assert_eq!(parse_only_if(message, only_mt1), Some(parse(message)));
+        assert!(matches!(
+            parse_only_if(message, only_mt1),
+            Some(Ok(ParsedMessage::MtStructured(_)))
+        ));
+
+        // not parsed, so the invalid RSS message does not fail
+        assert_eq!(parse_only_if("SS,A,123", only_mt1), None);
+        assert_eq!(parse_only_if("SS,X,999", only_mt1), None);
+        assert_eq!(parse_only_if("garbage", only_mt1), None);
+
+        assert!(matches!(
+            parse_only_if("MT1001000AL400C5", only_mt1),
+            Some(Err(ParseError::SizeNotMatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn cstr() {
+        use std::ffi::CString;
+
+        let message = CString::new("SS,1,042").unwrap();
+        assert_eq!(parse_cstr(&message), parse("SS,1,042"));
+
+        let message = CString::new(b"SS,1,\xff42".to_vec()).unwrap();
+        assert!(matches!(
+            parse_cstr(&message),
+            Err(ParseError::Utf8Error(_))
+        ));
+    }
+
+    #[test]
+    fn classify_prefix() {
+        assert_eq!(classify("SS,"), MessageKind::Rss);
+        assert_eq!(classify("SS,A,12345"), MessageKind::Rss);
+        assert_eq!(classify("MT1"), MessageKind::MtStructured);
+        assert_eq!(classify("MT1001000AL400C59"), MessageKind::MtStructured);
+        assert_eq!(classify("\r\nMT6001"), MessageKind::MtRaw);
+        assert_eq!(classify("MT"), MessageKind::Unknown);
+        assert_eq!(classify("MT2001"), MessageKind::Unknown);
+        assert_eq!(classify(""), MessageKind::Unknown);
+    }
+
+    #[test]
+    fn detect_prefix() {
+        assert_eq!(detect("SS,1,123"), MessageKind::Rss);
+        assert_eq!(detect("MT1"), MessageKind::MtStructured);
+        assert_eq!(detect("MT6001"), MessageKind::MtRaw);
+        assert_eq!(detect("MT2001"), MessageKind::Unknown);
+        assert_eq!(detect("SS"), MessageKind::Unknown);
+        assert_eq!(detect(" SS,1,123"), MessageKind::Unknown);
+        assert_eq!(detect("ÄSS,1,123"), MessageKind::Unknown);
+        assert_eq!(detect("garbage"), MessageKind::Unknown);
+        assert_eq!(detect(""), MessageKind::Unknown);
+    }
+
+    #[test]
+    fn fallback() {
+        let sentinel = parse("SS,1,000").unwrap();
+
+        assert_eq!(parse_with_fallback("garbage", sentinel.clone()), sentinel);
+        assert_eq!(parse_with_fallback("SS,X,123", sentinel.clone()), sentinel);
+        assert_eq!(parse_with_fallback("", sentinel.clone()), sentinel);
+        assert_eq!(
+            parse_with_fallback("SS,A,123", sentinel),
+            parse("SS,A,123").unwrap()
+        );
+    }
+
+    #[test]
+    fn verify_checksums() {
+        let messages: Vec<_> = [
+            "MT1001000AL400C592753572B323433212S1723756E4706",
+            "SS,A,123",
+            "MT1001000AL400C592753572B323433212S1723756E4707",
+            "garbage",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84C",
+        ]
+        .into_iter()
+        .map(|m| parse(m).unwrap())
+        .collect();
+
+        assert_eq!(
+            verify_all_checksums(&messages),
+            vec![(0, true), (2, false), (4, true), (5, false)]
+        );
+    }
+
     #[test]
     fn sample_usage() {
         let samples = vec![
@@ -111,7 +877,9 @@ mod tests {
             "MT0001000AL400C592753572B323433212S1723756E4706",
         ];
         for s in invalid_samples {
-            assert_eq!(parse(s).unwrap(), ParsedMessage::Invalid);
+            let parsed = parse(s).unwrap();
+            assert_eq!(parsed, ParsedMessage::Invalid(s.to_string()));
+            assert_eq!(parsed.to_raw_sentence(), s);
         }
 
         // smaller than expected
@@ -146,6 +914,146 @@ mod tests {
         assert!(parse("SS,X,123\n").is_err()); // invalid type
     }
 
+    #[test]
+    fn invalid_rss_type() {
+        assert_eq!(
+            parse("SS,X,123"),
+            Err(ParseError::InvalidRssType { found: 'X' })
+        );
+        assert_eq!(
+            parse("SS,2,123"),
+            Err(ParseError::InvalidRssType { found: '2' })
+        );
+    }
+
+    #[test]
+    fn confidence() {
+        let clean = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(clean.confidence(), 1.0);
+
+        // wrong checksum
+        let corrupt = parse("MT1001000AL400C592753572B323433212S1723756E4707").unwrap();
+        assert_eq!(corrupt.confidence(), 0.5);
+
+        // wrong checksum and latitude degrees out of range
+        let corrupt = parse("MT1001000AL400C592753572B323993212S1723756E4706").unwrap();
+        assert_eq!(corrupt.confidence(), 0.25);
+
+        let corrupt = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84C").unwrap();
+        assert_eq!(corrupt.confidence(), 0.5);
+
+        assert_eq!(parse("SS,A,123").unwrap().confidence(), 1.0);
+        assert_eq!(ParsedMessage::Invalid(String::new()).confidence(), 0.0);
+    }
+
+    #[test]
+    fn conversions() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let structured = MtStructured::try_from(parsed.clone()).unwrap();
+        assert_eq!(ParsedMessage::from(structured), parsed);
+
+        let parsed = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        let raw = MtRaw::try_from(parsed.clone()).unwrap();
+        assert_eq!(ParsedMessage::from(raw), parsed);
+
+        let parsed = parse("SS,A,123").unwrap();
+        let rss: Rss = parsed.clone().try_into().unwrap();
+        assert_eq!(ParsedMessage::from(rss), parsed);
+
+        // the original message is given back for re-use
+        let returned = MtStructured::try_from(parsed.clone()).unwrap_err();
+        assert_eq!(returned, parsed);
+        assert_eq!(MtRaw::try_from(returned).unwrap_err(), parsed);
+        assert_eq!(
+            Rss::try_from(ParsedMessage::Invalid("garbage".to_string())).unwrap_err(),
+            ParsedMessage::Invalid("garbage".to_string())
+        );
+    }
+
+    #[test]
+    fn retain_valid_messages() {
+        let results = vec![
+            "MT1001000AL400C592753572B323433212S1723756E4706",
+            "MT1001000AL400C592753572B323433212S172375",
+            "SS,A,123",
+            "MT2001000AL400C592753572B323433212S1723756E4706",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+            "SS,X,123",
+        ]
+        .into_iter()
+        .map(parse)
+        .collect();
+
+        let valid = retain_valid(results);
+        assert_eq!(valid.len(), 3);
+        assert!(matches!(valid[0], ParsedMessage::MtStructured(_)));
+        assert!(matches!(valid[1], ParsedMessage::Rss(_)));
+        assert!(matches!(valid[2], ParsedMessage::MtRaw(_)));
+    }
+
+    #[test]
+    fn many_bytes() {
+        let buf = b"MT1001000AL400C592753572B323433212S1723756E4706\r\nSS,A,123\r\n";
+        let results = parse_many_bytes(buf);
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Ok(ParsedMessage::MtStructured(_))));
+        assert!(matches!(results[1], Ok(ParsedMessage::Rss(_))));
+
+        // bare LF and CR separators
+        assert_eq!(parse_many_bytes(b"SS,A,123\nSS,1,123\rSS,A,001").len(), 3);
+
+        let results = parse_many_bytes(b"SS,A,123\r\n\xffSS,1,123\r\n");
+        assert!(matches!(results[..], [Err(ParseError::Utf8Error(_))]));
+    }
+
+    #[test]
+    fn position() {
+        let mt1 = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let mt6 = parse("MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+        assert!(mt1.position().is_some());
+        assert_eq!(mt6.position(), mt1.position());
+
+        // standard location test protocol, same location
+        let mt6 = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert_eq!(mt6.position(), mt1.position());
+
+        assert_eq!(parse("SS,A,123").unwrap().position(), None);
+        assert_eq!(ParsedMessage::Invalid(String::new()).position(), None);
+    }
+
+    #[test]
+    fn encode() {
+        let samples = [
+            "MT1001000AL400C592753572B323433212S1723756E4706",
+            "MT1001000AL400C592753572B323------S-------E4706",
+            "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B",
+            "SS,A,123",
+            "SS,1,001",
+        ];
+        for s in samples {
+            assert_eq!(parse(s).unwrap().encode().unwrap(), s);
+            assert_eq!(parse(s).unwrap().to_raw_sentence(), s);
+        }
+        assert_eq!(ParsedMessage::Invalid("garbage".to_string()).encode(), None);
+        assert_eq!(
+            parse(" garbage\r\n").unwrap().to_raw_sentence(),
+            " garbage\r\n"
+        );
+    }
+
+    #[test]
+    fn nmea() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(
+            parsed.to_nmea().unwrap(),
+            "$MT1001000AL400C592753572B323433212S1723756E4706*39\r\n"
+        );
+        assert_eq!(
+            ParsedMessage::Invalid("garbage".to_string()).to_nmea(),
+            None
+        );
+    }
+
     #[test]
     fn hardcoded_checksum() {
         assert_eq!(
@@ -159,7 +1067,105 @@ mod tests {
         if let Ok(ParsedMessage::MtRaw(v)) =
             parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B")
         {
-            assert_eq!(mt_raw::compute_checksum(&v.data.as_bytes()), v.checksum);
+            assert_eq!(mt_raw::compute_checksum(v.data.as_bytes()), v.checksum.0);
+        }
+    }
+
+    mod round_trip {
+        use proptest::prelude::*;
+
+        use super::*;
+        use crate::mt_structured::{CardinalDirection, MtMessageType};
+        use crate::rss::RssType;
+
+        fn sequence_number() -> impl Strategy<Value = usize> {
+            prop_oneof![Just(0), Just(511), 0..=511usize]
+        }
+
+        fn nnn() -> impl Strategy<Value = u8> {
+            prop_oneof![Just(0), Just(255), any::<u8>()]
+        }
+
+        fn rss() -> impl Strategy<Value = Rss> {
+            (
+                prop_oneof![Just(RssType::Frequency), Just(RssType::Alert)],
+                nnn(),
+            )
+                .prop_map(|(rss_type, nnn)| Rss { rss_type, nnn })
+        }
+
+        fn mt_structured(located: bool) -> impl Strategy<Value = MtStructured> {
+            let header = (
+                "[0-9]{3}",
+                sequence_number(),
+                prop_oneof![Just(MtMessageType::Test), Just(MtMessageType::Alert)],
+                prop_oneof![Just('S'), Just('L')],
+                "[0-9A-F]{15}",
+                "[0-9]{2}",
+            );
+            let location = (
+                (0..=90u8, 0..60u8, 0..60u8),
+                prop_oneof![
+                    Just(CardinalDirection::North),
+                    Just(CardinalDirection::South)
+                ],
+                (0..=180u16, 0..60u8, 0..60u8),
+                prop_oneof![Just(CardinalDirection::West), Just(CardinalDirection::East)],
+            );
+
+            (header, location).prop_map(
+                move |((id, sequence_number, message_type, format_flag, beacon, ss), location)| {
+                    let ((lat_d, lat_m, lat_s), lat_dir, (long_d, long_m, long_s), long_dir) =
+                        location;
+                    let mut msg = MtStructured {
+                        header: "MT1".to_string(),
+                        id: id.into(),
+                        sequence_number,
+                        message_type,
+                        format_flag,
+                        beacon,
+                        signal_strength: ss,
+                        lat_degrees: located.then_some(lat_d),
+                        lat_minutes: located.then_some(lat_m),
+                        lat_seconds: located.then_some(lat_s),
+                        lat_direction: if located {
+                            lat_dir
+                        } else {
+                            CardinalDirection::Unknown
+                        },
+                        long_degrees: located.then_some(long_d),
+                        long_minutes: located.then_some(long_m),
+                        long_seconds: located.then_some(long_s),
+                        long_direction: if located {
+                            long_dir
+                        } else {
+                            CardinalDirection::Unknown
+                        },
+                        checksum: Default::default(),
+                    };
+                    msg.checksum = mt_raw::compute_checksum(msg.body().as_bytes()).into();
+                    msg
+                },
+            )
+        }
+
+        proptest! {
+            #[test]
+            fn rss_round_trip(msg in rss()) {
+                prop_assert_eq!(parse(&msg.encode()), Ok(ParsedMessage::Rss(msg)));
+            }
+
+            #[test]
+            fn mt_structured_round_trip(msg in mt_structured(true)) {
+                prop_assert!(msg.verify_checksum());
+                prop_assert_eq!(parse(&msg.encode()), Ok(ParsedMessage::MtStructured(msg)));
+            }
+
+            #[test]
+            fn mt_structured_no_location_round_trip(msg in mt_structured(false)) {
+                prop_assert!(msg.encode()[28..43].chars().all(|c| c == '-'));
+                prop_assert_eq!(parse(&msg.encode()), Ok(ParsedMessage::MtStructured(msg)));
+            }
         }
     }
 }
@@ -0,0 +1,236 @@
+//! Message metrics
+//!
+//! [`MessageStats`] counts received messages per [`MessageKind`], e.g. to monitor a receiver.
+//! [`MessageRate`] computes the message rate over a sliding time window, e.g. to detect a device
+//! that stopped (or started flooding) messages.
+
+use std::collections::VecDeque;
+use std::ops::{Index, IndexMut};
+use std::time::{Duration, Instant};
+
+use crate::{MessageKind, ParsedMessage};
+
+/// Message counts per [`MessageKind`], indexable by kind.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{metrics::MessageStats, MessageKind};
+/// let mut stats = MessageStats::default();
+/// stats.extend(["SS,A,123", "SS,1,123"].map(|m| wte_mt_rx_parser::parse(m).unwrap()));
+/// assert_eq!(stats[MessageKind::Rss], 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MessageStats {
+    counts: [u64; 4],
+}
+
+impl MessageStats {
+    /// Counts `msg`.
+    pub fn record(&mut self, msg: &ParsedMessage) {
+        self[msg.kind()] += 1;
+    }
+
+    /// Returns how many messages were counted, of any kind.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// Returns the position of `kind` in [`MessageStats::counts`].
+fn slot(kind: MessageKind) -> usize {
+    match kind {
+        MessageKind::Rss => 0,
+        MessageKind::MtStructured => 1,
+        MessageKind::MtRaw => 2,
+        MessageKind::Unknown => 3,
+    }
+}
+
+impl Index<MessageKind> for MessageStats {
+    type Output = u64;
+
+    fn index(&self, kind: MessageKind) -> &u64 {
+        &self.counts[slot(kind)]
+    }
+}
+
+impl IndexMut<MessageKind> for MessageStats {
+    fn index_mut(&mut self, kind: MessageKind) -> &mut u64 {
+        &mut self.counts[slot(kind)]
+    }
+}
+
+impl Extend<ParsedMessage> for MessageStats {
+    fn extend<I: IntoIterator<Item = ParsedMessage>>(&mut self, iter: I) {
+        for msg in iter {
+            self.record(&msg);
+        }
+    }
+}
+
+/// Messages per second over a sliding time window.
+///
+/// ## Examples
+/// ```
+/// use std::time::Duration;
+/// use wte_mt_rx_parser::metrics::MessageRate;
+/// let mut rate = MessageRate::new(Duration::from_secs(10));
+/// for _ in 0..5 {
+///     rate.record();
+/// }
+/// assert_eq!(rate.count_in_window(), 5);
+/// assert_eq!(rate.rate(), 0.5);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageRate {
+    /// Length of the sliding window.
+    window: Duration,
+
+    /// Reception times of the messages within the window, oldest first.
+    timestamps: VecDeque<Instant>,
+}
+
+impl MessageRate {
+    /// Creates an empty rate over a sliding `window`.
+    pub fn new(window: Duration) -> Self {
+        MessageRate {
+            window,
+            timestamps: VecDeque::new(),
+        }
+    }
+
+    /// Records a message received now.
+    pub fn record(&mut self) {
+        self.record_at(Instant::now());
+    }
+
+    /// Records a message received `at` the given time, evicting messages that fall outside the
+    /// window ending at that time.
+    pub fn record_at(&mut self, at: Instant) {
+        self.timestamps.push_back(at);
+        while self
+            .timestamps
+            .front()
+            .is_some_and(|t| at.saturating_duration_since(*t) > self.window)
+        {
+            self.timestamps.pop_front();
+        }
+    }
+
+    /// Returns how many recorded messages are within the window ending now.
+    pub fn count_in_window(&self) -> usize {
+        self.count_in_window_at(Instant::now())
+    }
+
+    /// Returns how many recorded messages are within the window ending at `now`, i.e. received
+    /// at most the window length before `now`. Messages recorded after `now` are not counted.
+    pub fn count_in_window_at(&self, now: Instant) -> usize {
+        self.timestamps
+            .iter()
+            .filter(|t| {
+                now.checked_duration_since(**t)
+                    .is_some_and(|age| age <= self.window)
+            })
+            .count()
+    }
+
+    /// Returns the message rate, in messages per second, over the window ending now.
+    ///
+    /// Returns `0.0` for an empty window.
+    pub fn rate(&self) -> f64 {
+        self.rate_at(Instant::now())
+    }
+
+    /// Returns the message rate, in messages per second, over the window ending at `now`.
+    ///
+    /// Returns `0.0` for an empty window.
+    pub fn rate_at(&self, now: Instant) -> f64 {
+        if self.window.is_zero() {
+            return 0.0;
+        }
+        self.count_in_window_at(now) as f64 / self.window.as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn index_by_kind() {
+        let mut stats = MessageStats::default();
+        stats.extend(
+            [
+                "MT1001000AL400C592753572B323433212S1723756E4706",
+                "MT1001001AL400C592753572B323433212S1723756E4706",
+                "MT1001002AL400C592753572B323433212S1723756E4706",
+                "SS,A,123",
+            ]
+            .map(|m| parse(m).unwrap()),
+        );
+
+        assert_eq!(stats[MessageKind::MtStructured], 3);
+        assert_eq!(stats[MessageKind::Rss], 1);
+        assert_eq!(stats[MessageKind::MtRaw], 0);
+        assert_eq!(stats[MessageKind::Unknown], 0);
+
+        stats.record(&ParsedMessage::Invalid("garbage".to_string()));
+        stats[MessageKind::Rss] += 2;
+        assert_eq!(stats[MessageKind::Unknown], 1);
+        assert_eq!(stats[MessageKind::Rss], 3);
+        assert_eq!(stats.total(), 7);
+    }
+
+    #[test]
+    fn rate_over_window() {
+        let start = Instant::now();
+        let mut rate = MessageRate::new(Duration::from_secs(2));
+        for i in 0..10 {
+            rate.record_at(start + Duration::from_millis(200 * i));
+        }
+
+        // every message is in the past of `now`
+        let now = start + Duration::from_millis(2000);
+        assert_eq!(rate.count_in_window_at(now), 10);
+        assert_eq!(rate.rate_at(now), 5.0);
+
+        // the window slides past the oldest messages
+        let later = start + Duration::from_millis(2500);
+        assert_eq!(rate.count_in_window_at(later), 7);
+        assert_eq!(rate.rate_at(later), 3.5);
+
+        // messages after `now` are not counted
+        assert_eq!(rate.count_in_window_at(start), 1);
+
+        assert_eq!(MessageRate::new(Duration::ZERO).rate(), 0.0);
+    }
+
+    #[test]
+    fn rate_now() {
+        let mut rate = MessageRate::new(Duration::from_secs(60));
+        rate.record();
+        rate.record();
+        assert_eq!(rate.count_in_window(), 2);
+        assert_eq!(rate.rate(), 2.0 / 60.0);
+    }
+
+    #[test]
+    fn rate_evicts_old_messages() {
+        let start = Instant::now();
+        let mut rate = MessageRate::new(Duration::from_secs(1));
+        rate.record_at(start);
+        rate.record_at(start + Duration::from_millis(500));
+        assert_eq!(
+            rate.count_in_window_at(start + Duration::from_millis(500)),
+            2
+        );
+
+        rate.record_at(start + Duration::from_millis(1200));
+        assert_eq!(rate.timestamps.len(), 2);
+        rate.record_at(start + Duration::from_secs(3));
+        assert_eq!(rate.timestamps.len(), 1);
+        assert_eq!(rate.rate_at(start + Duration::from_secs(3)), 1.0);
+        assert_eq!(rate.rate_at(start + Duration::from_secs(5)), 0.0);
+    }
+}
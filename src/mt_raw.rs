@@ -9,12 +9,138 @@
 //! - `MT6` is fixed and actually “MT6”
 //! - `UUU`- is a 3 character MT-RX configurable ID – by default this is “001”
 //! - `NNN` -is a 3 decimal digit cycling packet sequence number from 000 to 511. This sequence number
-//! increments after each new test or distress message is received. After 511 the sequence cycles to 000
-//! and begins again.
+//!   increments after each new test or distress message is received. After 511 the sequence cycles to 000
+//!   and begins again.
 //! - `RRR`.. is 36 characters of raw data in a hex format.
 //! - `YYYY` – is a 4 character checksum (calculated from R – the first raw data character)
 
-use crate::ParseError;
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::beacon::Beacon;
+use crate::checksum::{Checksum, ChecksumBuilder};
+use crate::device::DeviceId;
+use crate::mt_structured::{CardinalDirection, MtMessageType, MtStructured, MAX_SEQUENCE_NUMBER};
+use crate::position::Position;
+use crate::sequence::SequenceNumber;
+use crate::{BuildError, ParseError};
+
+/// Length of a MT(6) message.
+pub const MT6_LEN: usize = 49;
+
+/// The 36 hex characters of raw data, i.e. the 18 bytes of the 406 MHz message.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw::HexData36;
+/// let data = HexData36::from_hex("FFFE2FA00E0000CBAB959DB0903788C71B79").unwrap();
+/// assert_eq!(data.decoded_bytes()[..3], [0xff, 0xfe, 0x2f]);
+/// assert_eq!(data.to_string(), "FFFE2FA00E0000CBAB959DB0903788C71B79");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HexData36([u8; 36]);
+
+impl HexData36 {
+    /// Tries to create raw data from 36 `hex` characters (either case, kept as is).
+    pub fn from_hex(hex: &str) -> Result<HexData36, ParseError> {
+        let chars: [u8; 36] = hex
+            .as_bytes()
+            .try_into()
+            .map_err(|_| ParseError::SizeNotMatch {
+                expected: 36,
+                found: hex.len(),
+            })?;
+        if !chars.iter().all(u8::is_ascii_hexdigit) {
+            return Err(ParseError::Invalid);
+        }
+        Ok(HexData36(chars))
+    }
+
+    /// Returns the raw data as hex characters.
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("hex data is ASCII")
+    }
+
+    /// Returns the 18 bytes of the 406 MHz message.
+    pub fn decoded_bytes(&self) -> [u8; 18] {
+        let nibble = |c: u8| (c as char).to_digit(16).expect("hex data is hex") as u8;
+        let mut bytes = [0; 18];
+        for (byte, pair) in bytes.iter_mut().zip(self.0.chunks_exact(2)) {
+            *byte = nibble(pair[0]) << 4 | nibble(pair[1]);
+        }
+        bytes
+    }
+}
+
+/// Encodes the 18 bytes of the 406 MHz message as uppercase hex.
+impl From<[u8; 18]> for HexData36 {
+    fn from(bytes: [u8; 18]) -> Self {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+        let mut chars = [0; 36];
+        for (pair, byte) in chars.chunks_exact_mut(2).zip(bytes) {
+            pair[0] = HEX[(byte >> 4) as usize];
+            pair[1] = HEX[(byte & 0xf) as usize];
+        }
+        HexData36(chars)
+    }
+}
+
+impl fmt::Display for HexData36 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A first generation 406 MHz message decoded from its 36 hex characters, independently of the
+/// MT(6) packet carrying it.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw::DecodedFrame;
+/// let frame = DecodedFrame::try_from(*b"FFFE2FA0062C93A9AB959E55EE7788C71B79").unwrap();
+/// assert_eq!(frame.beacon.hex_id(), "400C592752FFBFF");
+/// assert!(frame.position.is_some());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedFrame {
+    /// The 18 bytes of the 406 MHz message.
+    pub bytes: [u8; 18],
+
+    /// Message type, from the frame synchronization pattern (normal or self-test).
+    pub message_type: MtMessageType,
+
+    /// Whether the format flag (bit 25) is set, i.e. the message is long.
+    pub long_format: bool,
+
+    /// Beacon hex code, bits 26-85.
+    pub beacon: Beacon,
+
+    /// Encoded location, decoded as for [`MtRaw::into_mt_structured_via_decode`].
+    pub position: Option<Position>,
+}
+
+/// Decodes 36 hex characters (either case).
+///
+/// Fails with [`ParseError::Invalid`] if a character is not hex, or if the message does not start
+/// with a first generation bit/frame synchronization pattern.
+impl TryFrom<[u8; 36]> for DecodedFrame {
+    type Error = ParseError;
+
+    fn try_from(chars: [u8; 36]) -> Result<Self, Self::Error> {
+        let hex = std::str::from_utf8(&chars).map_err(|_| ParseError::Invalid)?;
+        let bytes = HexData36::from_hex(hex)?.decoded_bytes();
+        let message_type = frame_message_type(&bytes).ok_or(ParseError::Invalid)?;
+
+        Ok(DecodedFrame {
+            bytes,
+            message_type,
+            long_format: message_bits(&bytes, 25, 25) == 1,
+            beacon: Beacon::from_bits(message_bits(&bytes, 26, 85)),
+            position: decode_position(&bytes)
+                .map(|(lat, long)| Position::new(lat as f64 / 3600.0, long as f64 / 3600.0)),
+        })
+    }
+}
 
 /// MT Raw Data Serial Out Packet Format.
 #[derive(Clone, Debug, PartialEq)]
@@ -23,7 +149,7 @@ pub struct MtRaw {
     pub header: String,
 
     /// MT-RX configurable ID.
-    pub id: String,
+    pub id: DeviceId,
 
     /// Cycling packet sequence number.
     pub sequence_number: usize,
@@ -32,7 +158,490 @@ pub struct MtRaw {
     pub data: String,
 
     /// Checksum.
-    pub checksum: u16,
+    pub checksum: Checksum,
+
+    /// Structured form of the raw data, if decoded while parsing
+    /// (see [`crate::parser::Parser::decode_raw`]). `None` otherwise.
+    pub decoded: Option<MtStructured>,
+}
+
+impl MtRaw {
+    /// Returns the raw data as ASCII hex bytes, e.g. to compute its checksum.
+    pub fn data_as_slice(&self) -> &[u8] {
+        self.data.as_bytes()
+    }
+
+    /// Returns the number of ASCII hex characters of the raw data (not of decoded bytes), i.e.
+    /// 36 for a parsed message.
+    pub fn data_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the raw data as [`HexData36`], or [`ParseError`] if it is not 36 hex characters.
+    pub fn hex_data(&self) -> Result<HexData36, ParseError> {
+        HexData36::from_hex(&self.data)
+    }
+
+    /// Returns whether the stored checksum matches the one computed from the raw data.
+    #[must_use]
+    pub fn verify_checksum(&self) -> bool {
+        compute_checksum(self.data.as_bytes()) == self.checksum.0
+    }
+
+    /// Returns the stored checksum as 4 uppercase hex characters, as on the wire.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_raw;
+    /// let raw = mt_raw::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+    /// assert_eq!(raw.checksum_string(), "F84B");
+    /// ```
+    pub fn checksum_string(&self) -> String {
+        self.checksum.to_string()
+    }
+
+    /// Returns the checksum computed from the raw data as 4 uppercase hex characters, see
+    /// [`MtRaw::checksum_string`].
+    pub fn recomputed_checksum_string(&self) -> String {
+        Checksum(compute_checksum(self.data.as_bytes())).to_string()
+    }
+
+    /// Returns the message in its wire format (`MT6UUUNNNRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRYYYY`),
+    /// using the stored checksum.
+    pub fn encode(&self) -> String {
+        format!(
+            "{}{}{:03}{}{}",
+            self.header, self.id, self.sequence_number, self.data, self.checksum
+        )
+    }
+
+    /// Returns a multi-line description of the raw data as hex and decoded bytes, with the
+    /// checksum and whether it is valid. Meant for diagnostics, the format may change.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_raw;
+    /// let raw = mt_raw::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+    /// println!("{}", raw.debug_dump());
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let bytes = match decode_hex(&self.data) {
+            Ok(bytes) => bytes
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Err(err) => format!("not decodable ({})", err),
+        };
+        let validity = if self.verify_checksum() {
+            "valid"
+        } else {
+            "invalid"
+        };
+        format!(
+            "data: {}\nbytes: {}\nchecksum: {} ({})",
+            self.data, bytes, self.checksum, validity
+        )
+    }
+
+    /// Builds the wire format of a MT(6) message from its ID, sequence number and the 18 bytes of
+    /// the 406 MHz message, computing the checksum.
+    ///
+    /// Returns [`BuildError::InvalidId`] if `id` is not 3 printable ASCII characters.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::{mt_raw::MtRaw, sequence::SequenceNumber};
+    /// let data = [
+    ///     0xff, 0xfe, 0x2f, 0xa0, 0x0e, 0x00, 0x00, 0xcb, 0xab,
+    ///     0x95, 0x9d, 0xb0, 0x90, 0x37, 0x88, 0xc7, 0x1b, 0x79,
+    /// ];
+    /// let seq = SequenceNumber::new(1).unwrap();
+    /// assert_eq!(
+    ///     MtRaw::from_parts("001", seq, &data).unwrap(),
+    ///     "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B"
+    /// );
+    /// ```
+    pub fn from_parts(
+        id: &str,
+        seq: SequenceNumber,
+        data: &[u8; 18],
+    ) -> Result<String, BuildError> {
+        if id.len() != 3 || !id.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(BuildError::InvalidId {
+                found: id.to_string(),
+            });
+        }
+
+        let data = HexData36::from(*data);
+        let checksum = Checksum(compute_checksum(data.as_str().as_bytes()));
+        Ok(format!("MT6{}{}{}{}", id, seq, data, checksum))
+    }
+
+    /// Returns `len` bits of the decoded 406 MHz message starting at bit offset `start`
+    /// (MSB first, i.e. offset `0` is bit 1 of the COSPAS-SARSAT specification).
+    ///
+    /// Returns `None` if `len` is `0` or greater than 32, if the range exceeds the 144 decoded
+    /// bits, or if the raw data is not valid hex.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_raw;
+    /// let raw = mt_raw::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+    /// // bit synchronization pattern
+    /// assert_eq!(raw.decoded_bits(0, 15), Some(0x7fff));
+    /// ```
+    pub fn decoded_bits(&self, start: usize, len: usize) -> Option<u32> {
+        if len == 0 || len > 32 || start + len > 144 {
+            return None;
+        }
+        let bytes = decode_hex(&self.data).ok()?;
+        Some(message_bits(&bytes, start + 1, start + len) as u32)
+    }
+
+    /// Decodes the 406 MHz message carried in the raw data into a partial [`MtStructured`].
+    ///
+    /// Returns `Ok(None)` if the raw data does not start with a first generation bit/frame
+    /// synchronization pattern, or [`ParseError`] if the raw data is not valid hex.
+    ///
+    /// ## Notes
+    /// - The message type is derived from the frame synchronization pattern (normal or self-test).
+    /// - The beacon hex code is bits 26-85 of the 406 MHz message.
+    /// - The location is decoded for standard location protocols (with the PDF-2 offset, if
+    ///   available) and national location protocols (PDF-1 only).
+    /// - The signal strength is not carried by the raw data and is set to `"00"`.
+    /// - The checksum is computed from the decoded fields.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_raw;
+    /// let raw = mt_raw::parse("MT6001001FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+    /// let structured = raw.into_mt_structured_via_decode().unwrap().unwrap();
+    /// assert_eq!(structured.beacon, "400C592753572B3");
+    /// ```
+    pub fn into_mt_structured_via_decode(&self) -> Result<Option<MtStructured>, ParseError> {
+        let bytes = decode_hex(&self.data)?;
+        let bit = |first: usize, last: usize| message_bits(&bytes, first, last);
+
+        let Some(message_type) = frame_message_type(&bytes) else {
+            return Ok(None);
+        };
+        let format_flag = if bit(25, 25) == 1 { 'L' } else { 'S' };
+        let beacon = format!("{:015X}", bit(26, 85));
+
+        let mut msg = MtStructured {
+            header: "MT1".to_string(),
+            id: self.id.clone(),
+            sequence_number: self.sequence_number,
+            message_type,
+            format_flag,
+            beacon,
+            signal_strength: "00".to_string(),
+            lat_degrees: None,
+            lat_minutes: None,
+            lat_seconds: None,
+            lat_direction: CardinalDirection::Unknown,
+            long_degrees: None,
+            long_minutes: None,
+            long_seconds: None,
+            long_direction: CardinalDirection::Unknown,
+            checksum: Checksum::default(),
+        };
+
+        if let Some((lat, long)) = decode_position(&bytes) {
+            let (degrees, minutes, seconds) = to_dms(lat);
+            msg.lat_degrees = Some(degrees as u8);
+            msg.lat_minutes = Some(minutes);
+            msg.lat_seconds = Some(seconds);
+            msg.lat_direction = if lat < 0 {
+                CardinalDirection::South
+            } else {
+                CardinalDirection::North
+            };
+
+            let (degrees, minutes, seconds) = to_dms(long);
+            msg.long_degrees = Some(degrees);
+            msg.long_minutes = Some(minutes);
+            msg.long_seconds = Some(seconds);
+            msg.long_direction = if long < 0 {
+                CardinalDirection::West
+            } else {
+                CardinalDirection::East
+            };
+        }
+
+        msg.checksum = Checksum(compute_checksum(msg.body().as_bytes()));
+        Ok(Some(msg))
+    }
+}
+
+/// Returns the raw data as ASCII hex bytes, see [`MtRaw::data_as_slice`].
+impl AsRef<[u8]> for MtRaw {
+    fn as_ref(&self) -> &[u8] {
+        self.data_as_slice()
+    }
+}
+
+/// Formats the raw data only, uppercased, e.g. to feed it to other 406 MHz tools.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw;
+/// let raw = mt_raw::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+/// assert_eq!(format!("{:X}", raw), "FFFE2FA00E0000CBAB959DB0903788C71B79");
+/// ```
+impl fmt::UpperHex for MtRaw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.data.to_ascii_uppercase())
+    }
+}
+
+/// Formats the raw data only, lowercased.
+impl fmt::LowerHex for MtRaw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.data.to_ascii_lowercase())
+    }
+}
+
+/// Checks every constraint of `msg`, returning all violations as [`ParseError::InvalidField`]
+/// instead of stopping at the first one. An empty vector means `msg` is valid.
+///
+/// The following is checked:
+/// - `id` is 3 characters long.
+/// - `sequence_number` is at most [`MAX_SEQUENCE_NUMBER`].
+/// - `data` is 36 hex characters.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw;
+/// let parsed = mt_raw::parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+/// assert!(mt_raw::validate(&parsed).is_empty());
+/// ```
+pub fn validate(msg: &MtRaw) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    let mut check = |valid: bool, field: &'static str, raw: String| {
+        if !valid {
+            errors.push(ParseError::InvalidField { field, raw });
+        }
+    };
+
+    check(msg.id.as_str().len() == 3, "id", msg.id.to_string());
+    check(
+        msg.sequence_number <= MAX_SEQUENCE_NUMBER,
+        "sequence_number",
+        msg.sequence_number.to_string(),
+    );
+    check(decode_hex(&msg.data).is_ok(), "data", msg.data.clone());
+
+    errors
+}
+
+/// Decodes the 36 hex characters of raw data into the 18 bytes of the 406 MHz message.
+fn decode_hex(data: &str) -> Result<[u8; 18], ParseError> {
+    HexData36::from_hex(data).map(|data| data.decoded_bytes())
+}
+
+/// Returns the message type of a first generation 406 MHz message from its bit and frame
+/// synchronization patterns, or `None` if they are not recognized.
+fn frame_message_type(bytes: &[u8; 18]) -> Option<MtMessageType> {
+    if message_bits(bytes, 1, 15) != 0x7fff {
+        return None;
+    }
+    match message_bits(bytes, 16, 24) {
+        0b000101111 => Some(MtMessageType::Alert),
+        0b011010000 => Some(MtMessageType::Test),
+        _ => None,
+    }
+}
+
+/// Returns bits `first` to `last` (inclusive) of the 406 MHz message, numbered from 1 (MSB first)
+/// as in the COSPAS-SARSAT specification.
+fn message_bits(bytes: &[u8; 18], first: usize, last: usize) -> u64 {
+    (first..=last).fold(0, |acc, n| {
+        let bit = (bytes[(n - 1) / 8] >> (7 - (n - 1) % 8)) & 1;
+        (acc << 1) | bit as u64
+    })
+}
+
+/// Decodes the encoded position of location protocols, as signed (negative for South/West)
+/// latitude and longitude seconds of arc.
+fn decode_position(bytes: &[u8; 18]) -> Option<(i32, i32)> {
+    let bit = |first: usize, last: usize| message_bits(bytes, first, last) as i32;
+    let sign = |flag: i32, value: i32| if flag == 1 { -value } else { value };
+
+    let beacon = Beacon::from_bits(message_bits(bytes, 26, 85));
+    let (lat, long) = if beacon.is_standard_location() {
+        if bit(66, 74) == 0b1_1111_1111 && bit(76, 85) == 0b11_1111_1111 {
+            return None;
+        }
+        let mut lat = bit(66, 74) * 900;
+        let mut long = bit(76, 85) * 900;
+
+        // PDF-2 offsets, unless not available (default values)
+        if bit(107, 110) == 0b1101 && bit(113, 132) != 0b1111_1111_1111_1111_1111 {
+            let offset = |first: usize| {
+                let value = bit(first + 1, first + 5) * 60 + bit(first + 6, first + 9) * 4;
+                if bit(first, first) == 1 {
+                    value
+                } else {
+                    -value
+                }
+            };
+            lat += offset(113);
+            long += offset(123);
+        }
+        (sign(bit(65, 65), lat), sign(bit(75, 75), long))
+    } else if beacon.is_national_location() {
+        if bit(60, 66) == 0b111_1111 && bit(73, 80) == 0b1111_1111 {
+            return None;
+        }
+        let lat = bit(60, 66) * 3600 + bit(67, 71) * 120;
+        let long = bit(73, 80) * 3600 + bit(81, 85) * 120;
+        (sign(bit(59, 59), lat), sign(bit(72, 72), long))
+    } else {
+        return None;
+    };
+
+    if lat.abs() > 90 * 3600 || long.abs() > 180 * 3600 {
+        return None;
+    }
+    Some((lat, long))
+}
+
+/// BCH-1 generator polynomial, protecting bits 25 to 85.
+const BCH1_GENERATOR: u128 = 0x26D9E3;
+
+/// BCH-2 generator polynomial, protecting bits 107 to 132.
+const BCH2_GENERATOR: u128 = 0x1539;
+
+/// Standard location PDF-2 with the position offsets not available, bits 107-132
+/// (`1101 1 1 1 11111 1111 1 11111 1111`).
+const DEFAULT_PDF2: u64 = 0x37FFFFF;
+
+/// Encodes `msg` into the 18 bytes of a 406 MHz message, see [`MtStructured::to_raw`].
+pub(crate) fn encode_message(msg: &MtStructured) -> Result<[u8; 18], ParseError> {
+    let beacon = Beacon::from_hex(&msg.beacon)?;
+    let frame_sync = match msg.message_type {
+        MtMessageType::Alert => 0b000101111,
+        MtMessageType::Test => 0b011010000,
+        MtMessageType::Unknown => {
+            return Err(ParseError::InvalidField {
+                field: "message_type",
+                raw: msg.message_type.as_char().to_string(),
+            })
+        }
+    };
+    let long_format = match msg.format_flag {
+        'L' => true,
+        'S' => false,
+        c => {
+            return Err(ParseError::InvalidField {
+                field: "format_flag",
+                raw: c.to_string(),
+            })
+        }
+    };
+
+    let mut bytes = [0; 18];
+    set_message_bits(&mut bytes, 1, 15, 0x7fff);
+    set_message_bits(&mut bytes, 16, 24, frame_sync);
+    set_message_bits(&mut bytes, 25, 25, long_format as u64);
+    set_message_bits(&mut bytes, 26, 85, beacon.bits(26, 85));
+    let bch1 = bch_parity(message_bits(&bytes, 25, 85), 61, BCH1_GENERATOR, 21);
+    set_message_bits(&mut bytes, 86, 106, bch1);
+
+    if long_format {
+        let pdf2 = encode_pdf2(&beacon, msg).unwrap_or(DEFAULT_PDF2);
+        let bch2 = bch_parity(pdf2, 26, BCH2_GENERATOR, 12);
+        set_message_bits(&mut bytes, 107, 132, pdf2);
+        set_message_bits(&mut bytes, 133, 144, bch2);
+    }
+    Ok(bytes)
+}
+
+/// Encodes the offsets of the location of `msg` from the coarse position of a standard
+/// location `beacon` as PDF-2 (bits 107-132), or `None` if they can not be encoded.
+fn encode_pdf2(beacon: &Beacon, msg: &MtStructured) -> Option<u64> {
+    if !beacon.is_standard_location()
+        || (beacon.bits(66, 74) == 0b1_1111_1111 && beacon.bits(76, 85) == 0b11_1111_1111)
+    {
+        return None;
+    }
+
+    let lat_south = match msg.lat_direction {
+        CardinalDirection::North => false,
+        CardinalDirection::South => true,
+        _ => return None,
+    };
+    let long_west = match msg.long_direction {
+        CardinalDirection::East => false,
+        CardinalDirection::West => true,
+        _ => return None,
+    };
+    let lat = msg.lat_degrees? as u32 * 3600 + msg.lat_minutes? as u32 * 60;
+    let long = msg.long_degrees? as u32 * 3600 + msg.long_minutes? as u32 * 60;
+
+    // offset of `fine` seconds of arc (away from the equator / prime meridian) from the coarse
+    // quarter degrees, as 10 bits
+    let offset = |coarse_flag: u64, coarse: u64, flag: bool, fine: u32| {
+        if fine != 0 && flag != (coarse_flag == 1) {
+            return None;
+        }
+        let offset = fine as i64 - coarse as i64 * 900;
+        let steps = (offset.unsigned_abs() + 2) / 4;
+        let (minutes, steps) = (steps / 15, steps % 15);
+        (minutes <= 30).then_some(((offset >= 0) as u64) << 9 | (minutes << 4) | steps)
+    };
+    let lat = offset(
+        beacon.bits(65, 65),
+        beacon.bits(66, 74),
+        lat_south,
+        lat + msg.lat_seconds? as u32,
+    )?;
+    let long = offset(
+        beacon.bits(75, 75),
+        beacon.bits(76, 85),
+        long_west,
+        long + msg.long_seconds? as u32,
+    )?;
+
+    // fixed `1101`, internal navigation device, 121.5 MHz homing
+    Some(0b110111 << 20 | lat << 10 | long)
+}
+
+/// Sets bits `first` to `last` (inclusive) of the 406 MHz message to `value` (right aligned,
+/// bits beyond its 64 bits are cleared), see [`message_bits`].
+fn set_message_bits(bytes: &mut [u8; 18], first: usize, last: usize, value: u64) {
+    for n in first..=last {
+        let mask = 1 << (7 - (n - 1) % 8);
+        if value.checked_shr((last - n) as u32).unwrap_or(0) & 1 == 1 {
+            bytes[(n - 1) / 8] |= mask;
+        } else {
+            bytes[(n - 1) / 8] &= !mask;
+        }
+    }
+}
+
+/// Returns the BCH parity bits of the `len` bits of `data`, for a `generator` polynomial of
+/// the given `degree`.
+fn bch_parity(data: u64, len: usize, generator: u128, degree: usize) -> u64 {
+    let mut remainder = (data as u128) << degree;
+    for n in (degree..len + degree).rev() {
+        if (remainder >> n) & 1 == 1 {
+            remainder ^= generator << (n - degree);
+        }
+    }
+    remainder as u64
+}
+
+/// Splits signed seconds of arc into absolute degrees, minutes and seconds.
+fn to_dms(seconds: i32) -> (u16, u8, u8) {
+    let seconds = seconds.unsigned_abs();
+    (
+        (seconds / 3600) as u16,
+        (seconds / 60 % 60) as u8,
+        (seconds % 60) as u8,
+    )
 }
 
     /// Returns whether `message` is a valid MT(6) message.
@@ -42,15 +651,18 @@ pub struct MtRaw {
     /// use wte_mt_rx_parser::mt_raw;
     /// println!("is it MT6? {}", mt_raw::is_mt("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B"));
     /// ```
+    #[must_use]
     pub fn is_mt(message: &str) -> bool {
-        return message.starts_with("MT6");
+        message.starts_with("MT6")
     }
 
     /// Tries to parse a "Raw Data Serial Out Packet Format" `message`.
     ///
     /// ## Notes
     /// - Checksum is not calculated here. Use [`compute_checksum`] if you require
-    /// it to be correct.
+    ///   it to be correct.
+    /// - The raw data must be hex (either case), otherwise [`ParseError::InvalidField`] is
+    ///   returned.
     ///
     /// ## Examples
     /// ```
@@ -62,11 +674,15 @@ pub struct MtRaw {
     /// ## Message format
     /// Data provided should be in the following format:
     /// - `MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B`
+    #[must_use = "this returns the parse result without handling it"]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(message), ret, err(level = "warn"))
+    )]
     pub fn parse(message: &str) -> Result<MtRaw, ParseError> {
         // 012 345 678 901234567890123456789012345678901234 5678
         // MT6 UUU NNN RRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR YYYY
 
-        const MT6_LEN: usize = 49;
         if message.len() != MT6_LEN {
             return Err(ParseError::SizeNotMatch {
                 expected: MT6_LEN,
@@ -75,10 +691,16 @@ pub struct MtRaw {
         }
 
         let header = message[0..3].to_string();
-        let id = message[3..6].to_string();
+        let id = DeviceId::from(&message[3..6]);
         let sequence_number = message[6..9].parse::<usize>()?;
         let data = message[9..45].to_string();
-        let checksum = u16::from_str_radix(&message[45..49], 16)?;
+        if !data.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidField {
+                field: "data",
+                raw: data,
+            });
+        }
+        let checksum = Checksum(u16::from_str_radix(&message[45..49], 16)?);
 
         // TODO: calculate checksum here?
 
@@ -88,6 +710,7 @@ pub struct MtRaw {
             sequence_number,
             data,
             checksum,
+            decoded: None,
         };
 
         Ok(result)
@@ -104,15 +727,375 @@ pub struct MtRaw {
     ///     println!("not a valid checksum!");
     /// }
     /// ```
+    #[must_use]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(data_source), ret)
+    )]
     pub fn compute_checksum(data_source: &[u8]) -> u16 {
-        let mut checksum = 0;
-        for byte in data_source.iter() {
-            checksum ^= *byte as u16;
-            if (checksum & 0x8000) != 0 {
-                checksum = (checksum << 1) | 0x01;
-            } else {
-                checksum <<= 1;
-            }
+        compute_checksum_iter(data_source.iter().copied())
+    }
+
+    /// Calculate checksum of the bytes yielded by `data_source`, without requiring a buffer.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_raw;
+    /// let data = "FFFE2FA00E0000CBAB959DB0903788C71B79";
+    /// assert_eq!(mt_raw::compute_checksum_iter(data.bytes()), 0xf84b);
+    /// ```
+    #[must_use]
+    pub fn compute_checksum_iter(data_source: impl IntoIterator<Item = u8>) -> u16 {
+        let mut builder = ChecksumBuilder::new();
+        for byte in data_source {
+            builder.update(byte);
         }
-        checksum
+        builder.finalize().0
+    }
+
+/// Compares sequence numbers `a` and `b` accounting for the cycle back to `0` after
+/// [`MAX_SEQUENCE_NUMBER`], e.g. `510` comes before `2` with a window of at least `4`.
+///
+/// If `b` is at most `window` steps after `a` (modulo 512), `a` is [`Ordering::Less`]; if `a`
+/// is at most `window` steps after `b`, it is [`Ordering::Greater`]. Numbers further apart are
+/// compared by value. The window should be below 256 for the result to be consistent.
+///
+/// ## Examples
+/// ```
+/// use std::cmp::Ordering;
+/// use wte_mt_rx_parser::mt_raw;
+/// assert_eq!(mt_raw::seq_cmp(510, 2, 8), Ordering::Less);
+/// assert_eq!(mt_raw::seq_cmp(3, 5, 8), Ordering::Less);
+/// assert_eq!(mt_raw::seq_cmp(510, 2, 1), Ordering::Greater);
+/// ```
+pub fn seq_cmp(a: usize, b: usize, window: usize) -> Ordering {
+    const CYCLE: usize = MAX_SEQUENCE_NUMBER + 1;
+    let (a, b) = (a % CYCLE, b % CYCLE);
+
+    if a == b {
+        Ordering::Equal
+    } else if (b + CYCLE - a) % CYCLE <= window {
+        Ordering::Less
+    } else if (a + CYCLE - b) % CYCLE <= window {
+        Ordering::Greater
+    } else {
+        a.cmp(&b)
     }
+}
+
+/// Calculate checksum of the bytes of `s`, see [`compute_checksum`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw;
+/// assert_eq!(mt_raw::compute_checksum_from_str("FFFE2FA00E0000CBAB959DB0903788C71B79"), 0xf84b);
+/// ```
+#[must_use]
+pub fn compute_checksum_from_str(s: &str) -> u16 {
+    compute_checksum(s.as_bytes())
+}
+
+/// Returns the checksum of `s` as the 4 uppercase hex characters that end a MT packet.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_raw;
+/// assert_eq!(mt_raw::checksum_hex_string("FFFE2FA00E0000CBAB959DB0903788C71B79"), "F84B");
+/// ```
+#[must_use]
+pub fn checksum_hex_string(s: &str) -> String {
+    Checksum(compute_checksum_from_str(s)).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_structured;
+
+    #[test]
+    fn decode_matches_structured() {
+        // same alert as the MT1 sample (sequence number aligned)
+        let structured =
+            mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let raw = parse("MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+        let decoded = raw.into_mt_structured_via_decode().unwrap().unwrap();
+
+        assert_eq!(decoded.id, structured.id);
+        assert_eq!(decoded.sequence_number, structured.sequence_number);
+        assert_eq!(decoded.message_type, structured.message_type);
+        assert_eq!(decoded.format_flag, structured.format_flag);
+        assert_eq!(decoded.beacon, structured.beacon);
+        assert_eq!(decoded.lat_degrees, structured.lat_degrees);
+        assert_eq!(decoded.lat_minutes, structured.lat_minutes);
+        assert_eq!(decoded.lat_seconds, structured.lat_seconds);
+        assert_eq!(decoded.lat_direction, structured.lat_direction);
+        assert_eq!(decoded.long_degrees, structured.long_degrees);
+        assert_eq!(decoded.long_minutes, structured.long_minutes);
+        assert_eq!(decoded.long_seconds, structured.long_seconds);
+        assert_eq!(decoded.long_direction, structured.long_direction);
+        assert!(decoded.verify_checksum());
+    }
+
+    #[test]
+    fn hex_data() {
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        let data = raw.hex_data().unwrap();
+        assert_eq!(data.as_str(), raw.data);
+        assert_eq!(data.to_string(), raw.data);
+
+        let bytes = data.decoded_bytes();
+        assert_eq!(bytes[..4], [0xff, 0xfe, 0x2f, 0xa0]);
+        assert_eq!(bytes[17], 0x79);
+        assert_eq!(HexData36::from(bytes), data);
+
+        // lowercase is kept as is, but decodes the same
+        let lower = HexData36::from_hex("fffe2fa00e0000cbab959db0903788c71b79").unwrap();
+        assert_eq!(lower.as_str(), "fffe2fa00e0000cbab959db0903788c71b79");
+        assert_eq!(lower.decoded_bytes(), bytes);
+
+        assert_eq!(
+            HexData36::from_hex("FFFE2FA0"),
+            Err(ParseError::SizeNotMatch {
+                expected: 36,
+                found: 8
+            })
+        );
+        assert_eq!(
+            HexData36::from_hex("FFFE2FA00E0000CBAB959DB0903788C71B7Z"),
+            Err(ParseError::Invalid)
+        );
+    }
+
+    #[test]
+    fn bch() {
+        let bytes = decode_hex("FFFE2FA0062C93A9AB959E55EE7788C71B79").unwrap();
+        let bch1 = bch_parity(message_bits(&bytes, 25, 85), 61, BCH1_GENERATOR, 21);
+        assert_eq!(bch1, message_bits(&bytes, 86, 106));
+        let bch2 = bch_parity(message_bits(&bytes, 107, 132), 26, BCH2_GENERATOR, 12);
+        assert_eq!(bch2, message_bits(&bytes, 133, 144));
+
+        let mut encoded = [0; 18];
+        set_message_bits(&mut encoded, 1, 144, 0);
+        set_message_bits(&mut encoded, 9, 20, 0xabc);
+        assert_eq!(message_bits(&encoded, 9, 20), 0xabc);
+        assert_eq!(message_bits(&encoded, 1, 8), 0);
+    }
+
+    #[test]
+    fn decode_test_protocol() {
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        let decoded = raw.into_mt_structured_via_decode().unwrap().unwrap();
+        assert_eq!(decoded.beacon, "401C000197572B3");
+        assert_eq!(decoded.message_type, MtMessageType::Alert);
+        assert_eq!(decoded.format_flag, 'L');
+    }
+
+    #[test]
+    fn from_parts() {
+        let data = decode_hex("FFFE2FA0062C93A9AB959E55EE7788C71B79").unwrap();
+        let seq = SequenceNumber::new(511).unwrap();
+        let encoded = MtRaw::from_parts("ABC", seq, &data).unwrap();
+
+        let parsed = parse(&encoded).unwrap();
+        assert_eq!(parsed.id, "ABC");
+        assert_eq!(parsed.sequence_number, 511);
+        assert_eq!(parsed.data, "FFFE2FA0062C93A9AB959E55EE7788C71B79");
+        assert_eq!(parsed.checksum, Checksum(0x1131));
+        assert!(parsed.verify_checksum());
+
+        for id in ["01", "0001", "0 1", "0é"] {
+            assert_eq!(
+                MtRaw::from_parts(id, seq, &data),
+                Err(BuildError::InvalidId {
+                    found: id.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn non_hex_data() {
+        assert_eq!(
+            parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71BX9F84B"),
+            Err(ParseError::InvalidField {
+                field: "data",
+                raw: "FFFE2FA00E0000CBAB959DB0903788C71BX9".to_string()
+            })
+        );
+        assert!(parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B 9F84B").is_err());
+
+        let lowercase = parse("MT6001001fffe2fa00e0000cbab959db0903788c71b79F84B").unwrap();
+        assert_eq!(lowercase.data, "fffe2fa00e0000cbab959db0903788c71b79");
+        assert!(parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").is_ok());
+    }
+
+    #[test]
+    fn debug_dump() {
+        let mut raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        let dump = raw.debug_dump();
+        assert!(dump.contains("data: FFFE2FA00E0000CBAB959DB0903788C71B79\n"));
+        assert!(dump.contains("bytes: FF FE 2F A0 0E 00 00 CB AB 95 9D B0 90 37 88 C7 1B 79\n"));
+        assert!(dump.contains("checksum: F84B (valid)"));
+
+        raw.checksum = Checksum(0xf84c);
+        assert!(raw.debug_dump().contains("checksum: F84C (invalid)"));
+        raw.data.truncate(10);
+        assert!(raw.debug_dump().contains("bytes: not decodable"));
+    }
+
+    #[test]
+    fn decoded_bits() {
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert_eq!(raw.decoded_bits(0, 15), Some(0b111_1111_1111_1111));
+        assert_eq!(raw.decoded_bits(15, 9), Some(0b0_0010_1111));
+        assert_eq!(raw.decoded_bits(0, 32), Some(0xfffe2fa0));
+        assert_eq!(raw.decoded_bits(140, 4), Some(0x9));
+
+        assert_eq!(raw.decoded_bits(0, 0), None);
+        assert_eq!(raw.decoded_bits(0, 33), None);
+        assert_eq!(raw.decoded_bits(141, 4), None);
+    }
+
+    #[test]
+    fn validate_collects_all_errors() {
+        let parsed = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert!(validate(&parsed).is_empty());
+
+        let mut parsed = parse("MT6001512FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        parsed.data = "FFFE2FA00E0000CBAB959DB0903788C71BX9".to_string();
+        assert_eq!(
+            validate(&parsed),
+            vec![
+                ParseError::InvalidField {
+                    field: "sequence_number",
+                    raw: "512".to_string()
+                },
+                ParseError::InvalidField {
+                    field: "data",
+                    raw: "FFFE2FA00E0000CBAB959DB0903788C71BX9".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sequence_ordering() {
+        // in window
+        assert_eq!(seq_cmp(3, 5, 8), Ordering::Less);
+        assert_eq!(seq_cmp(5, 3, 8), Ordering::Greater);
+        assert_eq!(seq_cmp(7, 7, 8), Ordering::Equal);
+
+        // across the wrap
+        assert_eq!(seq_cmp(510, 2, 8), Ordering::Less);
+        assert_eq!(seq_cmp(2, 510, 8), Ordering::Greater);
+        assert_eq!(seq_cmp(511, 0, 1), Ordering::Less);
+
+        // out of window, by value
+        assert_eq!(seq_cmp(510, 2, 3), Ordering::Greater);
+        assert_eq!(seq_cmp(3, 300, 8), Ordering::Less);
+
+        let mut sequence = vec![1, 509, 0, 511, 510, 2];
+        sequence.sort_by(|a, b| seq_cmp(*a, *b, 16));
+        assert_eq!(sequence, vec![509, 510, 511, 0, 1, 2]);
+    }
+
+    #[test]
+    fn data_bytes() {
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert!(raw.verify_checksum());
+        assert_eq!(compute_checksum(raw.as_ref()), raw.checksum.0);
+        assert_eq!(raw.data_as_slice(), b"FFFE2FA00E0000CBAB959DB0903788C71B79");
+        assert_eq!(raw.data_len(), 36);
+    }
+
+    #[test]
+    fn checksum_strings() {
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert_eq!(raw.checksum_string(), "F84B");
+        assert_eq!(raw.recomputed_checksum_string(), "F84B");
+
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B790A4B").unwrap();
+        assert_eq!(raw.checksum_string(), "0A4B");
+        assert_eq!(raw.recomputed_checksum_string(), "F84B");
+    }
+
+    #[test]
+    fn decoded_frame() {
+        let raw = parse("MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap();
+        let chars: [u8; 36] = raw.data.as_bytes().try_into().unwrap();
+        let frame = DecodedFrame::try_from(chars).unwrap();
+        let structured = raw.into_mt_structured_via_decode().unwrap().unwrap();
+
+        assert_eq!(frame.bytes, raw.hex_data().unwrap().decoded_bytes());
+        assert_eq!(frame.message_type, MtMessageType::Alert);
+        assert!(frame.long_format);
+        assert_eq!(frame.beacon, Beacon::from_hex("400C592753572B3").unwrap());
+        let (position, expected) = (frame.position.unwrap(), structured.position().unwrap());
+        assert!((position.latitude - expected.latitude).abs() < 1e-9);
+        assert!((position.longitude - expected.longitude).abs() < 1e-9);
+
+        // lowercase
+        let frame = DecodedFrame::try_from(*b"fffe2fa0062c93a9ab959e55ee7788c71b79").unwrap();
+        assert_eq!(frame.beacon, Beacon::from_hex("400C592753572B3").unwrap());
+
+        // not hex
+        assert_eq!(
+            DecodedFrame::try_from(*b"FFFE2FA0062C93A9AB959E55EE7788C71B7G"),
+            Err(ParseError::Invalid)
+        );
+        // no synchronization pattern
+        assert_eq!(
+            DecodedFrame::try_from(*b"000E2FA00E0000CBAB959DB0903788C71B79"),
+            Err(ParseError::Invalid)
+        );
+    }
+
+    #[test]
+    fn hex_format() {
+        let raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert_eq!(format!("{:X}", raw), "FFFE2FA00E0000CBAB959DB0903788C71B79");
+        assert_eq!(format!("{:x}", raw), "fffe2fa00e0000cbab959db0903788c71b79");
+        assert_eq!(format!("{:X}", raw.checksum), "F84B");
+        assert_eq!(format!("{:X}", Checksum(0xF84B)), "F84B");
+        assert_eq!(format!("{:X}", Checksum(0x0F84)), "0F84");
+
+        let raw = parse("MT6001001fffe2fa00e0000cbab959db0903788c71b79F84B").unwrap();
+        assert_eq!(format!("{:X}", raw), "FFFE2FA00E0000CBAB959DB0903788C71B79");
+    }
+
+    #[test]
+    fn checksum_from_str() {
+        let data = "FFFE2FA00E0000CBAB959DB0903788C71B79";
+        assert_eq!(compute_checksum_from_str(data), 0xf84b);
+        assert_eq!(checksum_hex_string(data), "F84B");
+        assert_eq!(checksum_hex_string(""), "0000");
+        assert_eq!(
+            checksum_hex_string("MT1001000AL400C592753572B323433212S1723756E"),
+            "4706"
+        );
+    }
+
+    #[test]
+    fn checksum_iter() {
+        let data = "FFFE2FA00E0000CBAB959DB0903788C71B79";
+        assert_eq!(
+            compute_checksum_iter(data.bytes()),
+            compute_checksum(data.as_bytes())
+        );
+
+        // lazily produced bytes
+        let lazy = data.chars().map(|c| c as u8);
+        assert_eq!(compute_checksum_iter(lazy), 0xf84b);
+        assert_eq!(compute_checksum_iter(std::iter::empty()), 0);
+    }
+
+    #[test]
+    fn decode_unknown_sync() {
+        let raw = parse("MT6001001000E2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        assert_eq!(raw.into_mt_structured_via_decode(), Ok(None));
+
+        let mut raw = parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+        raw.data = "FFFE2FA00E0000CBAB959DB0903788C71BZZ".to_string();
+        assert!(raw.into_mt_structured_via_decode().is_err());
+    }
+}
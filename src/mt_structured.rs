@@ -7,13 +7,13 @@
 //! - `MT1` is fixed and actually “MT1”
 //! - `UUU` - is a 3 character MT-RX configurable ID – by default this is “001”
 //! - `NNN` -is a 3 decimal digit cycling packet sequence number from 000 to 511. This sequence number
-//! increments after each new test or distress message is received. After 511 the sequence cycles to 000
-//! and begins again.
+//!   increments after each new test or distress message is received. After 511 the sequence cycles to 000
+//!   and begins again.
 //! - `T` – is a single character message type 'T' or 'A' (test or distress alert)
 //! - `F` – is a single character format flag 'S' or 'L' (short or long) – this relates to the 406 beacon
-//! transmission specification.
+//!   transmission specification.
 //! - `HHHHHHHHHHHHHHH` – is a 15 character hex code used to define beacon owner and beacon
-//! capabilities as per the 406 beacon specification.
+//!   capabilities as per the 406 beacon specification.
 //! - `SS` – is a 2 character signal strength indication – “00” if not used.
 //! - `11` – is a 2 decimal character latitude degrees
 //! - `22` – is a 2 decimal character latitude minutes
@@ -29,8 +29,27 @@
 //! Legitimate example packet:
 //! `MT1001000AL400C592753572B323433212S1723756E4706`
 
+use thiserror::Error;
+
+use crate::beacon::{Beacon, BeaconAux};
+use crate::checksum::Checksum;
+use crate::device::DeviceId;
+use crate::mt_raw::{HexData36, MtRaw};
+use crate::position::Position;
+use crate::sequence::SequenceNumber;
 use crate::ParseError;
 
+pub use crate::mt_raw::{checksum_hex_string, compute_checksum_from_str};
+
+/// Highest cycling packet sequence number, after which the sequence cycles back to `0`.
+pub const MAX_SEQUENCE_NUMBER: usize = 511;
+
+/// Length of a MT(1) message.
+pub const MT1_LEN: usize = 47;
+
+/// Approximate distance covered by one second of arc of latitude, in meters.
+pub const METERS_PER_LAT_SECOND: f64 = 30.87;
+
 /// Represents a cardinal direction.
 #[derive(Clone, Debug, PartialEq)]
 pub enum CardinalDirection {
@@ -53,6 +72,46 @@ impl std::convert::From<char> for CardinalDirection {
     }
 }
 
+impl CardinalDirection {
+    /// Returns the character used by the MT-RX for this direction (`'-'` if unknown).
+    pub fn as_char(&self) -> char {
+        match self {
+            CardinalDirection::North => 'N',
+            CardinalDirection::South => 'S',
+            CardinalDirection::West => 'W',
+            CardinalDirection::East => 'E',
+            CardinalDirection::Unknown => '-',
+        }
+    }
+
+    /// Returns whether this direction belongs to a latitude ('N' or 'S').
+    pub fn is_latitude(&self) -> bool {
+        matches!(self, CardinalDirection::North | CardinalDirection::South)
+    }
+
+    /// Returns whether this direction belongs to a longitude ('W' or 'E').
+    pub fn is_longitude(&self) -> bool {
+        matches!(self, CardinalDirection::West | CardinalDirection::East)
+    }
+
+    /// Returns the opposite direction, on the same axis. `Unknown` stays `Unknown`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured::CardinalDirection;
+    /// assert_eq!(CardinalDirection::North.opposite(), CardinalDirection::South);
+    /// ```
+    pub fn opposite(&self) -> CardinalDirection {
+        match self {
+            CardinalDirection::North => CardinalDirection::South,
+            CardinalDirection::South => CardinalDirection::North,
+            CardinalDirection::West => CardinalDirection::East,
+            CardinalDirection::East => CardinalDirection::West,
+            CardinalDirection::Unknown => CardinalDirection::Unknown,
+        }
+    }
+}
+
 /// Represents a MT message type.
 #[derive(Clone, Debug, PartialEq)]
 pub enum MtMessageType {
@@ -71,6 +130,72 @@ impl std::convert::From<char> for MtMessageType {
     }
 }
 
+impl MtMessageType {
+    /// Returns whether this is a distress alert.
+    pub fn is_distress(&self) -> bool {
+        *self == MtMessageType::Alert
+    }
+
+    /// Returns whether this is a test message.
+    pub fn is_test(&self) -> bool {
+        *self == MtMessageType::Test
+    }
+
+    /// Returns the character used by the MT-RX for this message type (`'-'` if unknown).
+    pub fn as_char(&self) -> char {
+        match self {
+            MtMessageType::Test => 'T',
+            MtMessageType::Alert => 'A',
+            MtMessageType::Unknown => '-',
+        }
+    }
+}
+
+/// A defect found by [`MtStructured::validate`].
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum ValidationIssue {
+    /// The stored checksum differs from the one computed from the message fields.
+    #[error("checksum mismatch (stored {stored}, computed {computed})")]
+    ChecksumMismatch {
+        stored: Checksum,
+        computed: Checksum,
+    },
+
+    /// MT-RX ID not 3 characters long.
+    #[error("invalid MT-RX ID (expected 3 characters, found {found:?})")]
+    InvalidId { found: String },
+
+    /// Sequence number above [`MAX_SEQUENCE_NUMBER`].
+    #[error(
+        "sequence number out of range (expected at most {MAX_SEQUENCE_NUMBER}, found {found})"
+    )]
+    SequenceOutOfRange { found: usize },
+
+    /// Message type neither 'T' nor 'A'.
+    #[error("unknown message type")]
+    UnknownMessageType,
+
+    /// Format flag neither 'S' nor 'L' (see [`MtStructured::format_flag_is_known`]).
+    #[error("unknown format flag {found:?}")]
+    UnknownFormatFlag { found: char },
+
+    /// Beacon hex code not 15 hex characters.
+    #[error("invalid beacon hex code {found:?}")]
+    InvalidBeacon { found: String },
+
+    /// Location fields out of range (see [`MtStructured::coordinates_in_range`]).
+    #[error("location out of range")]
+    CoordinatesOutOfRange,
+
+    /// Unknown latitude or longitude direction (see [`MtStructured::directions_are_valid`]).
+    #[error("unknown direction (latitude {lat:?}, longitude {long:?})")]
+    UnknownDirection { lat: char, long: char },
+
+    /// Latitude direction 'W'/'E' or longitude direction 'N'/'S'.
+    #[error("direction on the wrong axis (latitude {lat:?}, longitude {long:?})")]
+    DirectionAxisMismatch { lat: char, long: char },
+}
+
 /// MT Serial Out Packet Format.
 #[derive(Clone, Debug, PartialEq)]
 pub struct MtStructured {
@@ -78,7 +203,7 @@ pub struct MtStructured {
     pub header: String,
 
     /// MT-RX configurable ID.
-    pub id: String,
+    pub id: DeviceId,
 
     /// Cycling packet sequence number.
     pub sequence_number: usize,
@@ -121,7 +246,560 @@ pub struct MtStructured {
 
     /// Checksum.
     /// If there is no location information available, hence the value will be `0`.
-    pub checksum: u16,
+    pub checksum: Checksum,
+}
+
+impl MtStructured {
+    /// Returns whether the beacon transmitted a short format (`'S'`) message.
+    ///
+    /// ## Notes
+    /// - The 406 specification defines the short message as carrying a 15 hex character
+    ///   identification and the long message as carrying 22 hex characters, but the MT-RX
+    ///   always outputs the 15 character beacon hex code regardless of the format flag.
+    pub fn is_short_format(&self) -> bool {
+        self.format_flag == 'S'
+    }
+
+    /// Returns whether the beacon transmitted a long format (`'L'`) message.
+    ///
+    /// See [`MtStructured::is_short_format`] for notes on the beacon field length.
+    pub fn is_long_format(&self) -> bool {
+        self.format_flag == 'L'
+    }
+
+    /// Returns whether the format flag is either `'S'` or `'L'`.
+    pub fn format_flag_is_known(&self) -> bool {
+        self.is_short_format() || self.is_long_format()
+    }
+
+    /// Returns the location as a decimal [`Position`], or `None` if not available
+    /// (any location field missing, or unknown directions).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// let position = parsed.position().unwrap();
+    /// assert!((position.latitude - -43.536667).abs() < 1e-6);
+    /// ```
+    pub fn position(&self) -> Option<Position> {
+        let decimal = |degrees: f64, minutes: u8, seconds: u8| {
+            degrees + minutes as f64 / 60.0 + seconds as f64 / 3600.0
+        };
+
+        let latitude = decimal(
+            self.lat_degrees? as f64,
+            self.lat_minutes?,
+            self.lat_seconds?,
+        );
+        let longitude = decimal(
+            self.long_degrees? as f64,
+            self.long_minutes?,
+            self.long_seconds?,
+        );
+
+        let latitude = match self.lat_direction {
+            CardinalDirection::North => latitude,
+            CardinalDirection::South => -latitude,
+            _ => return None,
+        };
+        let longitude = match self.long_direction {
+            CardinalDirection::East => longitude,
+            CardinalDirection::West => -longitude,
+            _ => return None,
+        };
+
+        Some(Position::new(latitude, longitude))
+    }
+
+    /// Returns the location as WGS-84 decimal degrees `(latitude, longitude)`, negative for
+    /// South and West, or `None` if not available (see [`MtStructured::position`]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// let (lat, lon) = parsed.lat_lon_wgs84().unwrap();
+    /// assert!((lat - -43.537).abs() < 1e-3 && (lon - 172.632).abs() < 1e-3);
+    /// ```
+    pub fn lat_lon_wgs84(&self) -> Option<(f64, f64)> {
+        self.position().map(|p| (p.latitude, p.longitude))
+    }
+
+    /// Returns the location as `(longitude, latitude)`, the order used by GeoJSON, see
+    /// [`MtStructured::lat_lon_wgs84`].
+    pub fn lon_lat_wgs84(&self) -> Option<(f64, f64)> {
+        self.lat_lon_wgs84().map(|(lat, lon)| (lon, lat))
+    }
+
+    /// Returns the estimated positional uncertainty of the location, in meters.
+    ///
+    /// Locations are given with a resolution of one second of arc, which is roughly
+    /// [`METERS_PER_LAT_SECOND`] along a meridian and never more along a parallel, so that is the
+    /// uncertainty of any available location. Returns [`f64::INFINITY`] if the location is not
+    /// available (see [`MtStructured::position`]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert!((parsed.coordinate_precision_meters() - 31.0).abs() < 1.0);
+    /// ```
+    pub fn coordinate_precision_meters(&self) -> f64 {
+        match self.position() {
+            Some(_) => METERS_PER_LAT_SECOND,
+            None => f64::INFINITY,
+        }
+    }
+
+    /// Returns the location components labeled by field name, latitude first, e.g. for generic
+    /// rendering. Missing components are `None`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(parsed.coordinate_fields()[0], ("lat_degrees", Some(43)));
+    /// ```
+    pub fn coordinate_fields(&self) -> Vec<(&'static str, Option<u16>)> {
+        vec![
+            ("lat_degrees", self.lat_degrees.map(u16::from)),
+            ("lat_minutes", self.lat_minutes.map(u16::from)),
+            ("lat_seconds", self.lat_seconds.map(u16::from)),
+            ("long_degrees", self.long_degrees),
+            ("long_minutes", self.long_minutes.map(u16::from)),
+            ("long_seconds", self.long_seconds.map(u16::from)),
+        ]
+    }
+
+    /// Returns the location as a NMEA `$GPRMC` sentence (including the trailing `<CR><LF>`),
+    /// or `None` if not available (see [`MtStructured::position`]).
+    ///
+    /// The UTC time field is filled from `timestamp` if given. Speed, course, date and magnetic
+    /// variation are not known and left empty.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(
+    ///     parsed.to_nmea_rmc(None).unwrap(),
+    ///     "$GPRMC,,A,4332.2000,S,17237.9333,E,,,,,*0E\r\n"
+    /// );
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn to_nmea_rmc(&self, timestamp: Option<chrono::NaiveTime>) -> Option<String> {
+        use chrono::Timelike;
+
+        self.position()?;
+        let minutes = |minutes: u8, seconds: u8| minutes as f64 + seconds as f64 / 60.0;
+        let time = timestamp.map_or(String::new(), |t| {
+            format!(
+                "{:02}{:02}{:02}.{:02}",
+                t.hour(),
+                t.minute(),
+                t.second(),
+                t.nanosecond() % 1_000_000_000 / 10_000_000
+            )
+        });
+
+        let body = format!(
+            "GPRMC,{},A,{:02}{:07.4},{},{:03}{:07.4},{},,,,,",
+            time,
+            self.lat_degrees?,
+            minutes(self.lat_minutes?, self.lat_seconds?),
+            self.lat_direction.as_char(),
+            self.long_degrees?,
+            minutes(self.long_minutes?, self.long_seconds?),
+            self.long_direction.as_char(),
+        );
+        Some(crate::nmea_sentence(&body))
+    }
+
+    /// Returns the message as a GeoJSON `Feature`, with a `Point` geometry at the location (or a
+    /// `null` geometry if not available) and [`MtStructured::as_geojson_properties`] as
+    /// properties.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// let feature = parsed.to_geojson_feature();
+    /// assert_eq!(feature["geometry"]["type"], "Point");
+    /// assert_eq!(feature["properties"]["beacon"], "400C592753572B3");
+    /// ```
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson_feature(&self) -> serde_json::Value {
+        let geometry = match self.lon_lat_wgs84() {
+            Some((lon, lat)) => serde_json::json!({
+                "type": "Point",
+                "coordinates": [lon, lat],
+            }),
+            None => serde_json::Value::Null,
+        };
+
+        serde_json::json!({
+            "type": "Feature",
+            "geometry": geometry,
+            "properties": self.as_geojson_properties(),
+        })
+    }
+
+    /// Returns the message fields as GeoJSON feature properties, e.g. to build a custom feature.
+    ///
+    /// Keys are `id`, `sequence_number`, `message_type`, `beacon`, `signal_strength`,
+    /// `lat_decimal`, `lon_decimal` (`null` if the location is not available) and `checksum`
+    /// (4 uppercase hex characters). `signal_strength` is `null` if not numeric.
+    #[cfg(feature = "geojson")]
+    pub fn as_geojson_properties(&self) -> serde_json::Map<String, serde_json::Value> {
+        use serde_json::Value;
+
+        let (lat, lon) = self.lat_lon_wgs84().unzip();
+        let mut properties = serde_json::Map::new();
+        properties.insert("id".into(), Value::from(self.id.as_str()));
+        properties.insert("sequence_number".into(), Value::from(self.sequence_number));
+        properties.insert(
+            "message_type".into(),
+            Value::from(self.message_type.as_char().to_string()),
+        );
+        properties.insert("beacon".into(), Value::from(self.beacon.as_str()));
+        properties.insert(
+            "signal_strength".into(),
+            Value::from(self.signal_strength.parse::<u8>().ok()),
+        );
+        properties.insert("lat_decimal".into(), Value::from(lat));
+        properties.insert("lon_decimal".into(), Value::from(lon));
+        properties.insert("checksum".into(), Value::from(self.checksum.to_string()));
+        properties
+    }
+
+    /// Returns the 60 bits of the beacon hex code right aligned, i.e. message bits 26 to 85.
+    ///
+    /// Returns `None` if the beacon hex code is not 15 hex characters.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(parsed.beacon_bits(), Some(0x400C592753572B3));
+    /// ```
+    pub fn beacon_bits(&self) -> Option<u64> {
+        Beacon::from_hex(&self.beacon)
+            .ok()
+            .map(|beacon| beacon.bits(26, 85))
+    }
+
+    /// Returns the MMSI carried by the beacon hex code (see [`Beacon::mmsi`]).
+    ///
+    /// Returns `None` for non-maritime beacons, or if the beacon hex code can not be decoded.
+    pub fn beacon_mmsi(&self) -> Option<u32> {
+        Beacon::from_hex(&self.beacon).ok()?.mmsi()
+    }
+
+    /// Returns the beacon hex code in uppercase.
+    pub fn format_beacon_hex(&self) -> String {
+        self.beacon.to_ascii_uppercase()
+    }
+
+    /// Returns the value (0 to 15) of every hex character of the beacon hex code.
+    ///
+    /// Characters that are not hex, as well as missing ones if the beacon hex code is shorter
+    /// than 15 characters, are `0`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(parsed.beacon_nibbles()[..4], [4, 0, 0, 12]);
+    /// ```
+    pub fn beacon_nibbles(&self) -> [u8; 15] {
+        let mut nibbles = [0; 15];
+        for (nibble, c) in nibbles.iter_mut().zip(self.beacon.chars()) {
+            *nibble = c.to_digit(16).unwrap_or(0) as u8;
+        }
+        nibbles
+    }
+
+    /// Returns the 15 Hex ID of the beacon (see [`Beacon::hex_id`]), used to look the beacon up
+    /// in a registry.
+    ///
+    /// If the beacon hex code can not be decoded, it is returned uppercased.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(parsed.beacon_15hex_id(), "400C592752FFBFF");
+    /// ```
+    pub fn beacon_15hex_id(&self) -> String {
+        match Beacon::from_hex(&self.beacon) {
+            Ok(beacon) => beacon.hex_id(),
+            Err(_) => self.beacon.to_ascii_uppercase(),
+        }
+    }
+
+    /// Returns whether `other` was sent by the same beacon, i.e. both have the same 15 Hex ID
+    /// (see [`MtStructured::beacon_15hex_id`]), regardless of their encoded positions.
+    pub fn same_beacon(&self, other: &MtStructured) -> bool {
+        self.beacon_15hex_id() == other.beacon_15hex_id()
+    }
+
+    /// Returns whether `other` describes the same physical alert, i.e. both have the same MT-RX
+    /// ID, beacon hex code and sequence number.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let message = "MT1001000AL400C592753572B323433212S1723756E4706";
+    /// let first = mt_structured::parse(message).unwrap();
+    /// assert!(first.same_alert_as(&mt_structured::parse(message).unwrap()));
+    /// ```
+    pub fn same_alert_as(&self, other: &MtStructured) -> bool {
+        self.id == other.id
+            && self.sequence_number == other.sequence_number
+            && self.beacon.eq_ignore_ascii_case(&other.beacon)
+    }
+
+    /// Returns whether this message is a retransmission of the `earlier` packet, i.e. it
+    /// describes the same alert (see [`MtStructured::same_alert_as`]) with the same checksum.
+    pub fn is_retransmission_of(&self, earlier: &MtStructured) -> bool {
+        self.same_alert_as(earlier) && self.checksum == earlier.checksum
+    }
+
+    /// Returns the country code carried by the beacon hex code (see [`Beacon::country_code`]).
+    ///
+    /// Returns `None` if the beacon hex code can not be decoded.
+    pub fn beacon_country_code(&self) -> Option<u16> {
+        Beacon::from_hex(&self.beacon)
+            .ok()
+            .map(|b| b.country_code())
+    }
+
+    /// Returns the MT-RX ID as a number, or [`ParseError::InvalidField`] if it is not
+    /// 3 decimal digits.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(parsed.id_as_u16(), Ok(1));
+    /// ```
+    pub fn id_as_u16(&self) -> Result<u16, ParseError> {
+        self.id.to_u16().ok_or_else(|| ParseError::InvalidField {
+            field: "id",
+            raw: self.id.to_string(),
+        })
+    }
+
+    /// Returns the sequence number, or [`ParseError::InvalidField`] if it is above
+    /// [`MAX_SEQUENCE_NUMBER`].
+    pub fn sequence(&self) -> Result<SequenceNumber, ParseError> {
+        u16::try_from(self.sequence_number)
+            .ok()
+            .and_then(SequenceNumber::new)
+            .ok_or_else(|| ParseError::InvalidField {
+                field: "sequence_number",
+                raw: self.sequence_number.to_string(),
+            })
+    }
+
+    /// Returns the message type, or [`ParseError::InvalidField`] if it is unknown.
+    pub fn message_type_required(&self) -> Result<MtMessageType, ParseError> {
+        match self.message_type {
+            MtMessageType::Unknown => Err(ParseError::InvalidField {
+                field: "message_type",
+                raw: self.message_type.as_char().to_string(),
+            }),
+            _ => Ok(self.message_type.clone()),
+        }
+    }
+
+    /// Returns whether the stored checksum matches the one computed from the message fields.
+    ///
+    /// ## Notes
+    /// - The message is re-encoded from its fields, so unparsable location characters
+    ///   (stored as `None`) are assumed to have been '-'.
+    #[must_use]
+    pub fn verify_checksum(&self) -> bool {
+        crate::mt_raw::compute_checksum(self.body().as_bytes()) == self.checksum.0
+    }
+
+    /// Returns the stored checksum as 4 uppercase hex characters, as on the wire.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(parsed.checksum_string(), "4706");
+    /// ```
+    pub fn checksum_string(&self) -> String {
+        self.checksum.to_string()
+    }
+
+    /// Returns the checksum computed from the re-encoded message as 4 uppercase hex characters,
+    /// see [`MtStructured::verify_checksum`].
+    pub fn recomputed_checksum_string(&self) -> String {
+        Checksum(crate::mt_raw::compute_checksum(self.body().as_bytes())).to_string()
+    }
+
+    /// Returns whether the latitude direction is 'N' or 'S' and the longitude direction is 'W' or 'E'.
+    /// Unknown directions are accepted when the respective location is not available.
+    pub fn directions_are_valid(&self) -> bool {
+        let lat_ok = self.lat_direction.is_latitude()
+            || (self.lat_direction == CardinalDirection::Unknown && self.lat_degrees.is_none());
+        let long_ok = self.long_direction.is_longitude()
+            || (self.long_direction == CardinalDirection::Unknown && self.long_degrees.is_none());
+        lat_ok && long_ok
+    }
+
+    /// Returns whether every available location field is within its valid range
+    /// (latitude degrees up to 90, longitude degrees up to 180, minutes and seconds below 60).
+    pub fn coordinates_in_range(&self) -> bool {
+        let below = |value: Option<u8>, max: u8| value.is_none_or(|v| v < max);
+        self.lat_degrees.is_none_or(|v| v <= 90)
+            && below(self.lat_minutes, 60)
+            && below(self.lat_seconds, 60)
+            && self.long_degrees.is_none_or(|v| v <= 180)
+            && below(self.long_minutes, 60)
+            && below(self.long_seconds, 60)
+    }
+
+    /// Encodes the message as the raw 406 MHz message it was decoded from, i.e. the inverse
+    /// of [`MtRaw::into_mt_structured_via_decode`], computing the MT(6) checksum.
+    ///
+    /// Returns [`ParseError`] if the beacon hex code, message type or format flag can not be
+    /// encoded.
+    ///
+    /// ## Notes
+    /// The conversion is lossy for the parts of the 406 MHz message that MT(1) does not carry:
+    /// - The BCH error correcting codes are recomputed.
+    /// - Short format messages carry no PDF-2, bits 107 to 144 are left `0`.
+    /// - Long format standard location messages carry the offsets of the location from the
+    ///   beacon coarse position, rounded to 4 seconds. Offsets are set to "not available" if
+    ///   the location is not available or too far from the coarse position, as is the whole
+    ///   PDF-2 of other protocols. The position source and 121.5 MHz homing bits are set.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert_eq!(
+    ///     parsed.to_raw().unwrap().encode(),
+    ///     "MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131"
+    /// );
+    /// ```
+    pub fn to_raw(&self) -> Result<MtRaw, ParseError> {
+        let data = HexData36::from(crate::mt_raw::encode_message(self)?).to_string();
+        Ok(MtRaw {
+            header: "MT6".to_string(),
+            id: self.id.clone(),
+            sequence_number: self.sequence_number,
+            checksum: Checksum(crate::mt_raw::compute_checksum(data.as_bytes())),
+            data,
+            decoded: None,
+        })
+    }
+
+    /// Checks the checksum and every field of the message, returning every defect found, in
+    /// field order after the checksum. An empty vector means the message is valid.
+    ///
+    /// See [`validate`] for the same checks (except the checksum) reported as [`ParseError`]s.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::mt_structured;
+    /// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// assert!(parsed.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let (lat, long) = (self.lat_direction.as_char(), self.long_direction.as_char());
+
+        let computed = Checksum(crate::mt_raw::compute_checksum(self.body().as_bytes()));
+        if computed != self.checksum {
+            issues.push(ValidationIssue::ChecksumMismatch {
+                stored: self.checksum,
+                computed,
+            });
+        }
+        if self.id.as_str().len() != 3 {
+            issues.push(ValidationIssue::InvalidId {
+                found: self.id.to_string(),
+            });
+        }
+        if self.sequence_number > MAX_SEQUENCE_NUMBER {
+            issues.push(ValidationIssue::SequenceOutOfRange {
+                found: self.sequence_number,
+            });
+        }
+        if self.message_type == MtMessageType::Unknown {
+            issues.push(ValidationIssue::UnknownMessageType);
+        }
+        if !self.format_flag_is_known() {
+            issues.push(ValidationIssue::UnknownFormatFlag {
+                found: self.format_flag,
+            });
+        }
+        if self.beacon.len() != 15 || !self.beacon.bytes().all(|b| b.is_ascii_hexdigit()) {
+            issues.push(ValidationIssue::InvalidBeacon {
+                found: self.beacon.clone(),
+            });
+        }
+        if !self.coordinates_in_range() {
+            issues.push(ValidationIssue::CoordinatesOutOfRange);
+        }
+        if self.lat_direction.is_longitude() || self.long_direction.is_latitude() {
+            issues.push(ValidationIssue::DirectionAxisMismatch { lat, long });
+        } else if !self.directions_are_valid() {
+            issues.push(ValidationIssue::UnknownDirection { lat, long });
+        }
+
+        issues
+    }
+
+    /// Returns the message in its wire format (`MT1UUUNNNTFHHHHHHHHHHHHHHHSS112233N4445566WYYYY`),
+    /// using the stored checksum.
+    pub fn encode(&self) -> String {
+        format!("{}{}", self.body(), self.checksum)
+    }
+
+    /// Re-encodes the message fields, without the checksum (`MT1UUUNNNTFHHHHHHHHHHHHHHHSS112233N4445566W`).
+    pub(crate) fn body(&self) -> String {
+        format!(
+            "{}{}{:03}{}{}{}{}{}",
+            self.header,
+            self.id,
+            self.sequence_number,
+            self.message_type.as_char(),
+            self.format_flag,
+            self.beacon,
+            self.signal_strength,
+            self.location(),
+        )
+    }
+
+    /// Returns the location characters as on the wire (`112233N4445566W`), with `-` for
+    /// unknown fields.
+    fn location(&self) -> String {
+        fn digits<T: std::fmt::Display>(value: Option<T>, width: usize) -> String {
+            match value {
+                Some(v) => format!("{:0width$}", v, width = width),
+                None => "-".repeat(width),
+            }
+        }
+
+        format!(
+            "{}{}{}{}{}{}{}{}",
+            digits(self.lat_degrees, 2),
+            digits(self.lat_minutes, 2),
+            digits(self.lat_seconds, 2),
+            self.lat_direction.as_char(),
+            digits(self.long_degrees, 3),
+            digits(self.long_minutes, 2),
+            digits(self.long_seconds, 2),
+            self.long_direction.as_char(),
+        )
+    }
 }
 
 /// Returns whether `message` is a valid MT(1) message.
@@ -131,8 +809,9 @@ pub struct MtStructured {
 /// use wte_mt_rx_parser::mt_structured;
 /// println!("is it MT1? {}", mt_structured::is_mt("MT1001000AL400C592753572B323433212S1723756E4706"));
 /// ```
+#[must_use]
 pub fn is_mt(message: &str) -> bool {
-    return message.starts_with("MT1");
+    message.starts_with("MT1")
 }
 
 /// Tries to parse a "MT Serial Out Packet Format" `message`.
@@ -150,11 +829,15 @@ pub fn is_mt(message: &str) -> bool {
 /// ## Message format
 /// Data provided should be in the following format:
 /// - `MT1UUUNNNTFHHHHHHHHHHHHHHHSS112233N4445566WYYYY`
+#[must_use = "this returns the parse result without handling it"]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(message), ret, err(level = "warn"))
+)]
 pub fn parse(message: &str) -> Result<MtStructured, ParseError> {
     // 012 345 678 9 0 123456789012345 67 89 01 23 4 567 89 01 2 3456
     // MT1 UUU NNN T F HHHHHHHHHHHHHHH SS 11 22 33 N 444 55 66 W YYYY
 
-    const MT1_LEN: usize = 47;
     if message.len() != MT1_LEN {
         return Err(ParseError::SizeNotMatch {
             expected: MT1_LEN,
@@ -163,7 +846,7 @@ pub fn parse(message: &str) -> Result<MtStructured, ParseError> {
     }
 
     let header = message[0..3].to_string();
-    let id = message[3..6].to_string();
+    let id = DeviceId::from(&message[3..6]);
     let sequence_number = message[6..9].parse::<usize>()?;
     let message_type = (message.as_bytes()[9] as char).into();
     let format_flag = message.as_bytes()[10] as char;
@@ -177,7 +860,7 @@ pub fn parse(message: &str) -> Result<MtStructured, ParseError> {
     let long_minutes = message[38..40].parse::<u8>().ok();
     let long_seconds = message[40..42].parse::<u8>().ok();
     let long_direction = (message.as_bytes()[42] as char).into();
-    let checksum = u16::from_str_radix(&message[43..47], 16).unwrap_or(0);
+    let checksum = Checksum(u16::from_str_radix(&message[43..47], 16).unwrap_or(0));
 
     // TODO: calculate checksum here?
 
@@ -203,10 +886,344 @@ pub fn parse(message: &str) -> Result<MtStructured, ParseError> {
     Ok(result)
 }
 
+/// Decodes the auxiliary identification data of a `beacon` hex code (see [`Beacon::aux`]).
+///
+/// Returns `None` if the beacon hex code can not be decoded or carries no supported data.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{beacon::BeaconAux, mt_structured};
+/// assert_eq!(mt_structured::beacon_aux("C009D6502815401"), Some(BeaconAux::Mmsi(512123456)));
+/// ```
+pub fn beacon_aux(beacon: &str) -> Option<BeaconAux> {
+    Beacon::from_hex(beacon).ok()?.aux()
+}
+
+/// Returns the coarse position encoded in a `beacon` hex code, if it uses a location protocol.
+///
+/// This can be used to cross-check the MT-RX location fields. See [`Beacon::position`] for details.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_structured;
+/// let position = mt_structured::beacon_position("400C592753572B3").unwrap();
+/// assert_eq!((position.latitude, position.longitude), (-43.5, 172.75));
+/// ```
+pub fn beacon_position(beacon: &str) -> Option<Position> {
+    Beacon::from_hex(beacon).ok()?.position()
+}
+
+/// Checks every constraint of `msg`, returning all violations as [`ParseError::InvalidField`]
+/// (or [`ParseError::DirectionAxisMismatch`]) instead of stopping at the first one. An empty
+/// vector means `msg` is valid.
+///
+/// The following is checked:
+/// - `id` is 3 characters long.
+/// - `sequence_number` is at most [`MAX_SEQUENCE_NUMBER`].
+/// - `message_type` is known.
+/// - `format_flag` is 'S' or 'L'.
+/// - `beacon` is a 15 character hex code.
+/// - location fields are within range and directions are valid (see
+///   [`MtStructured::coordinates_in_range`] and [`MtStructured::directions_are_valid`]).
+/// - the latitude direction is not 'W'/'E' and the longitude direction is not 'N'/'S'.
+///
+/// These are the checks of [`MtStructured::validate`], except the checksum, which is not
+/// checked.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::mt_structured;
+/// let parsed = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+/// assert!(mt_structured::validate(&parsed).is_empty());
+/// ```
+pub fn validate(msg: &MtStructured) -> Vec<ParseError> {
+    msg.validate()
+        .into_iter()
+        .filter_map(|issue| issue_error(msg, issue))
+        .collect()
+}
+
+/// Returns the [`ParseError`] reported by [`validate`] for `issue` of `msg`, or `None` for
+/// issues it does not report.
+fn issue_error(msg: &MtStructured, issue: ValidationIssue) -> Option<ParseError> {
+    let error = match issue {
+        ValidationIssue::ChecksumMismatch { .. } => return None,
+        ValidationIssue::InvalidId { found } => ParseError::InvalidField {
+            field: "id",
+            raw: found,
+        },
+        ValidationIssue::SequenceOutOfRange { found } => ParseError::InvalidField {
+            field: "sequence_number",
+            raw: found.to_string(),
+        },
+        ValidationIssue::UnknownMessageType => ParseError::InvalidField {
+            field: "message_type",
+            raw: msg.message_type.as_char().to_string(),
+        },
+        ValidationIssue::UnknownFormatFlag { found } => ParseError::InvalidField {
+            field: "format_flag",
+            raw: found.to_string(),
+        },
+        ValidationIssue::InvalidBeacon { found } => ParseError::InvalidField {
+            field: "beacon",
+            raw: found,
+        },
+        ValidationIssue::CoordinatesOutOfRange => ParseError::InvalidField {
+            field: "location",
+            raw: msg.location(),
+        },
+        ValidationIssue::UnknownDirection { lat, long } => ParseError::InvalidField {
+            field: "direction",
+            raw: format!("{}{}", lat, long),
+        },
+        ValidationIssue::DirectionAxisMismatch { lat, long } => {
+            ParseError::DirectionAxisMismatch { lat, long }
+        }
+    };
+    Some(error)
+}
+
+/// Tries to parse a "Serial Out Packet Format" `message`, rejecting it if [`validate`] finds
+/// any violation (the first one is returned).
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{mt_structured, ParseError};
+/// assert!(mt_structured::parse_strict("MT1001000AL400C592753572B323433212S1723756E4706").is_ok());
+/// assert_eq!(
+///     mt_structured::parse_strict("MT1001000AL400C592753572B323433212E1723756E4706"),
+///     Err(ParseError::DirectionAxisMismatch { lat: 'E', long: 'E' })
+/// );
+/// ```
+#[must_use = "this returns the parse result without handling it"]
+pub fn parse_strict(message: &str) -> Result<MtStructured, ParseError> {
+    let msg = parse(message)?;
+    match validate(&msg).into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(msg),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn strict_direction_axis() {
+        assert!(parse_strict("MT1001000AL400C592753572B323433212S1723756E4706").is_ok());
+        assert_eq!(
+            parse_strict("MT1001000AL400C592753572B323433212E1723756E4706"),
+            Err(ParseError::DirectionAxisMismatch {
+                lat: 'E',
+                long: 'E'
+            })
+        );
+        assert_eq!(
+            parse_strict("MT1001000AL400C592753572B323433212S1723756N4706"),
+            Err(ParseError::DirectionAxisMismatch {
+                lat: 'S',
+                long: 'N'
+            })
+        );
+    }
+
+    #[test]
+    fn wgs84_pairs() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let (lat, lon) = parsed.lat_lon_wgs84().unwrap();
+        assert!((lat - -43.537).abs() < 1e-3);
+        assert!((lon - 172.632).abs() < 1e-3);
+        assert_eq!(parsed.lon_lat_wgs84(), Some((lon, lat)));
+
+        let parsed = parse("MT1001000AL400C592753572B323---------------4706").unwrap();
+        assert_eq!(parsed.lat_lon_wgs84(), None);
+        assert_eq!(parsed.lon_lat_wgs84(), None);
+    }
+
+    #[test]
+    fn coordinate_precision() {
+        // a meridian is ~20004 km long, spanning 180 degrees
+        let expected = 20_003_931.0 / (180.0 * 3600.0);
+        assert!((METERS_PER_LAT_SECOND - expected).abs() < 0.01);
+
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.coordinate_precision_meters(), METERS_PER_LAT_SECOND);
+        let parsed = parse("MT1001000AL400C592753572B323---------------4706").unwrap();
+        assert_eq!(parsed.coordinate_precision_meters(), f64::INFINITY);
+    }
+
+    #[test]
+    fn coordinate_fields() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(
+            parsed.coordinate_fields(),
+            vec![
+                ("lat_degrees", Some(43)),
+                ("lat_minutes", Some(32)),
+                ("lat_seconds", Some(12)),
+                ("long_degrees", Some(172)),
+                ("long_minutes", Some(37)),
+                ("long_seconds", Some(56)),
+            ]
+        );
+
+        let parsed = parse("MT1001000AL400C592753572B323---------------4706").unwrap();
+        assert!(parsed.coordinate_fields().iter().all(|(_, v)| v.is_none()));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn nmea_rmc() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let sentence = parsed.to_nmea_rmc(None).unwrap();
+        assert!(sentence.starts_with("$GPRMC,"));
+        let fields: Vec<_> = sentence.split(',').collect();
+        assert_eq!(fields[1], "");
+        assert_eq!(fields[2], "A");
+        assert_eq!(&fields[3..7], ["4332.2000", "S", "17237.9333", "E"]);
+
+        let checksum = sentence[1..sentence.len() - 5]
+            .bytes()
+            .fold(0, |acc, b| acc ^ b);
+        assert!(sentence.ends_with(&format!("*{:02X}\r\n", checksum)));
+
+        let time = chrono::NaiveTime::from_hms_milli_opt(9, 5, 7, 250).unwrap();
+        let sentence = parsed.to_nmea_rmc(Some(time)).unwrap();
+        assert!(sentence.starts_with("$GPRMC,090507.25,A,"));
+
+        let parsed = parse("MT1001000AL400C592753572B323---------------4706").unwrap();
+        assert_eq!(parsed.to_nmea_rmc(None), None);
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn geojson() {
+        use serde_json::{json, Value};
+
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let properties = parsed.as_geojson_properties();
+        assert_eq!(properties["id"], "001");
+        assert_eq!(properties["sequence_number"], 0);
+        assert_eq!(properties["message_type"], "A");
+        assert_eq!(properties["beacon"], "400C592753572B3");
+        assert_eq!(properties["signal_strength"], 23);
+        assert!((properties["lat_decimal"].as_f64().unwrap() - -43.537).abs() < 1e-3);
+        assert!((properties["lon_decimal"].as_f64().unwrap() - 172.632).abs() < 1e-3);
+        assert_eq!(properties["checksum"], "4706");
+        assert_eq!(properties.len(), 8);
+
+        let feature = parsed.to_geojson_feature();
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(
+            feature["geometry"]["coordinates"],
+            json!([properties["lon_decimal"], properties["lat_decimal"]])
+        );
+        assert_eq!(feature["properties"], Value::Object(properties));
+
+        let parsed = parse("MT1001000AL400C592753572B323---------------4706").unwrap();
+        let properties = parsed.as_geojson_properties();
+        assert_eq!(properties.len(), 8);
+        assert_eq!(properties["lat_decimal"], Value::Null);
+        assert_eq!(properties["lon_decimal"], Value::Null);
+        assert_eq!(parsed.to_geojson_feature()["geometry"], Value::Null);
+    }
+
+    #[test]
+    fn checksum_strings() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.checksum_string(), "4706");
+        assert_eq!(parsed.recomputed_checksum_string(), "4706");
+
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E00AB").unwrap();
+        assert_eq!(parsed.checksum_string(), "00AB");
+        assert_eq!(parsed.recomputed_checksum_string(), "4706");
+    }
+
+    #[test]
+    fn direction_axis() {
+        use CardinalDirection::*;
+
+        for (direction, latitude, longitude) in [
+            (North, true, false),
+            (South, true, false),
+            (West, false, true),
+            (East, false, true),
+            (Unknown, false, false),
+        ] {
+            assert_eq!(direction.is_latitude(), latitude, "{:?}", direction);
+            assert_eq!(direction.is_longitude(), longitude, "{:?}", direction);
+        }
+    }
+
+    #[test]
+    fn to_raw() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let raw = parsed.to_raw().unwrap();
+        assert_eq!(
+            raw,
+            crate::mt_raw::parse("MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131").unwrap()
+        );
+        assert!(raw.verify_checksum());
+
+        // every field is preserved through the round trip but the signal strength (and
+        // therefore the checksum)
+        let mut decoded = raw.into_mt_structured_via_decode().unwrap().unwrap();
+        assert_eq!(decoded.signal_strength, "00");
+        decoded.signal_strength = parsed.signal_strength.clone();
+        decoded.checksum = parsed.checksum;
+        assert_eq!(decoded, parsed);
+
+        // test message without location
+        let parsed = parse("MT1002511TL400C592753572B323---------------4706").unwrap();
+        let decoded = parsed
+            .to_raw()
+            .unwrap()
+            .into_mt_structured_via_decode()
+            .unwrap()
+            .unwrap();
+        assert_eq!(decoded.id, "002");
+        assert_eq!(decoded.sequence_number, 511);
+        assert_eq!(decoded.message_type, MtMessageType::Test);
+        assert_eq!(decoded.beacon, parsed.beacon);
+        assert_eq!(decoded.position(), beacon_position(&parsed.beacon));
+
+        // short format, user protocol
+        let parsed = parse("MT1001000ASC009D650281540123---------------4706").unwrap();
+        let decoded = parsed
+            .to_raw()
+            .unwrap()
+            .into_mt_structured_via_decode()
+            .unwrap()
+            .unwrap();
+        assert_eq!(decoded.format_flag, 'S');
+        assert_eq!(decoded.beacon, parsed.beacon);
+
+        let mut parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        parsed.message_type = MtMessageType::Unknown;
+        assert!(parsed.to_raw().is_err());
+        parsed.message_type = MtMessageType::Alert;
+        parsed.format_flag = 'X';
+        assert!(parsed.to_raw().is_err());
+        parsed.format_flag = 'L';
+        parsed.beacon = "400C59".to_string();
+        assert!(parsed.to_raw().is_err());
+    }
+
+    #[test]
+    fn direction_opposite() {
+        use CardinalDirection::*;
+
+        assert_eq!(North.opposite(), South);
+        assert_eq!(South.opposite(), North);
+        assert_eq!(West.opposite(), East);
+        assert_eq!(East.opposite(), West);
+        assert_eq!(Unknown.opposite(), Unknown);
+        for direction in [North, South, West, East, Unknown] {
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(direction.opposite().is_latitude(), direction.is_latitude());
+        }
+    }
+
     #[test]
     fn fields() {
         // MT1 UUU NNN T F HHHHHHHHHHHHHHH SS 11 22 33 N 444 55 66 W YYYY
@@ -219,6 +1236,28 @@ mod tests {
         assert_eq!(parsed.format_flag, 'L');
         assert_eq!(parsed.beacon, "400C592753572B3");
         assert_eq!(parsed.signal_strength, "23");
+        assert_eq!(parsed.checksum, Checksum(0x4706));
+        assert_eq!(parsed.checksum.to_string(), "4706");
+    }
+
+    #[test]
+    fn format_flag() {
+        // MT1 001 000 A L 400C592753572B3 23 43 32 12 S 172 37 56 E 4706 <- long format
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert!(parsed.is_long_format());
+        assert!(!parsed.is_short_format());
+        assert!(parsed.format_flag_is_known());
+        assert_eq!(parsed.beacon.len(), 15);
+
+        // MT1 001 000 A S 400C592753572B3 23 43 32 12 S 172 37 56 E 4706 <- short format
+        let parsed = parse("MT1001000AS400C592753572B323433212S1723756E4706").unwrap();
+        assert!(parsed.is_short_format());
+        assert!(!parsed.is_long_format());
+        assert_eq!(parsed.beacon.len(), 15);
+
+        // MT1 001 000 A X 400C592753572B3 23 43 32 12 S 172 37 56 E 4706 <- unknown format
+        let parsed = parse("MT1001000AX400C592753572B323433212S1723756E4706").unwrap();
+        assert!(!parsed.format_flag_is_known());
     }
 
     #[test]
@@ -239,6 +1278,289 @@ mod tests {
         assert_eq!(parsed.long_direction, CardinalDirection::East);
     }
 
+    #[test]
+    fn message_type() {
+        assert!(MtMessageType::Alert.is_distress());
+        assert!(!MtMessageType::Alert.is_test());
+        assert!(!MtMessageType::Test.is_distress());
+        assert!(MtMessageType::Test.is_test());
+        assert!(!MtMessageType::Unknown.is_distress());
+        assert!(!MtMessageType::Unknown.is_test());
+    }
+
+    #[test]
+    fn required_fields() {
+        let parsed = parse("MT1001511AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.id_as_u16(), Ok(1));
+        assert_eq!(parsed.sequence(), Ok(SequenceNumber::new(511).unwrap()));
+        assert_eq!(parsed.message_type_required(), Ok(MtMessageType::Alert));
+
+        let parsed = parse("MT1A01512XL400C592753572B323433212S1723756E4706").unwrap();
+        let invalid = |field, raw: &str| ParseError::InvalidField {
+            field,
+            raw: raw.to_string(),
+        };
+        assert_eq!(parsed.id_as_u16(), Err(invalid("id", "A01")));
+        assert_eq!(parsed.sequence(), Err(invalid("sequence_number", "512")));
+        assert_eq!(
+            parsed.message_type_required(),
+            Err(invalid("message_type", "-"))
+        );
+    }
+
+    #[test]
+    fn beacon_bits() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_bits(), Some(0x400C592753572B3));
+        let parsed = parse("MT1001000ALc009d650281540123433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_bits(), Some(0xC009D6502815401));
+        let parsed = parse("MT1001000AL400C5927535XYZ323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_bits(), None);
+    }
+
+    #[test]
+    fn beacon_hex_format() {
+        let parsed = parse("MT1001000AL400c592753572b323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.format_beacon_hex(), "400C592753572B3");
+
+        let nibbles = parsed.beacon_nibbles();
+        assert_eq!(nibbles[0], 4);
+        assert_eq!(nibbles, [4, 0, 0, 12, 5, 9, 2, 7, 5, 3, 5, 7, 2, 11, 3]);
+
+        let parsed = parse("MT1001000AL400C5927535XYZ323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_nibbles()[10..], [5, 0, 0, 0, 3]);
+    }
+
+    #[test]
+    fn beacon_15hex_id() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_15hex_id(), "400C592752FFBFF");
+
+        let parsed = parse("MT1001000ALc009d650281540123433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_15hex_id(), "C009D6502815401");
+        let parsed = parse("MT1001000AL400c5927535xyz323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_15hex_id(), "400C5927535XYZ3");
+    }
+
+    #[test]
+    fn beacon_mmsi() {
+        // maritime EPIRB (user protocol), MMSI 512123456
+        let parsed = parse("MT1001000ALC009D650281540123433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_mmsi(), Some(512123456));
+        assert_eq!(parsed.beacon_country_code(), Some(512));
+
+        // PLB (standard location protocol)
+        let parsed = parse("MT1001000AL400E592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_mmsi(), None);
+        assert_eq!(parsed.beacon_country_code(), Some(512));
+
+        // not a beacon hex code
+        let parsed = parse("MT1001000AL400E592753572BZ23433212S1723756E4706").unwrap();
+        assert_eq!(parsed.beacon_mmsi(), None);
+        assert_eq!(parsed.beacon_country_code(), None);
+    }
+
+    #[test]
+    fn checksum() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert!(parsed.verify_checksum());
+
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4707").unwrap();
+        assert!(!parsed.verify_checksum());
+    }
+
+    #[test]
+    fn position() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let position = parsed.position().unwrap();
+        assert!((position.latitude - -(43.0 + 32.0 / 60.0 + 12.0 / 3600.0)).abs() < 1e-9);
+        assert!((position.longitude - (172.0 + 37.0 / 60.0 + 56.0 / 3600.0)).abs() < 1e-9);
+
+        let parsed = parse("MT1001000AL400C592753572B323433212N1723756W4706").unwrap();
+        let position = parsed.position().unwrap();
+        assert!(position.latitude > 0.0 && position.longitude < 0.0);
+
+        let parsed = parse("MT1001000AL400C592753572B323433212S-------E4706").unwrap();
+        assert!(parsed.position().is_none());
+        let parsed = parse("MT1001000AL400C592753572B323433212X1723756E4706").unwrap();
+        assert!(parsed.position().is_none());
+    }
+
+    #[test]
+    fn beacon_position_matches_location() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let position = beacon_position(&parsed.beacon).unwrap();
+
+        // 43 32 12 S and 172 37 56 E, rounded to the nearest 1/4 degree
+        assert_eq!(position, Position::new(-43.5, 172.75));
+        assert!(beacon_position("C00800000000000").is_none());
+        assert!(beacon_position("not a beacon").is_none());
+    }
+
+    #[test]
+    fn validate_issues() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert_eq!(parsed.validate(), vec![]);
+
+        // bad sequence number, and therefore checksum mismatch
+        let parsed = parse("MT1001999AL400C592753572B323433212S1723756E4706").unwrap();
+        let issues = parsed.validate();
+        assert_eq!(issues.len(), 2);
+        assert!(matches!(
+            issues[0],
+            ValidationIssue::ChecksumMismatch {
+                stored: Checksum(0x4706),
+                ..
+            }
+        ));
+        assert_eq!(
+            issues[1],
+            ValidationIssue::SequenceOutOfRange { found: 999 }
+        );
+
+        // longitude out of range and unknown direction, with a matching checksum
+        let mut parsed = parse("MT1001000AL400C592753572B323433212S1813756X0000").unwrap();
+        parsed.checksum = Checksum(crate::mt_raw::compute_checksum(parsed.body().as_bytes()));
+        assert_eq!(
+            parsed.validate(),
+            vec![
+                ValidationIssue::CoordinatesOutOfRange,
+                ValidationIssue::UnknownDirection {
+                    lat: 'S',
+                    long: '-'
+                },
+            ]
+        );
+
+        // every field check, in field order
+        let mut parsed = parse("MT1001000XX400C592753572BZ23433212S1723756E4706").unwrap();
+        parsed.id = DeviceId::from("1");
+        parsed.checksum = Checksum(crate::mt_raw::compute_checksum(parsed.body().as_bytes()));
+        assert_eq!(
+            parsed.validate(),
+            vec![
+                ValidationIssue::InvalidId {
+                    found: "1".to_string()
+                },
+                ValidationIssue::UnknownMessageType,
+                ValidationIssue::UnknownFormatFlag { found: 'X' },
+                ValidationIssue::InvalidBeacon {
+                    found: "400C592753572BZ".to_string()
+                },
+            ]
+        );
+
+        let parsed = parse("MT1001000AL400C592753572B323433212E1723756E4706").unwrap();
+        assert!(parsed
+            .validate()
+            .contains(&ValidationIssue::DirectionAxisMismatch {
+                lat: 'E',
+                long: 'E'
+            }));
+    }
+
+    #[test]
+    fn validate_collects_all_errors() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        assert!(validate(&parsed).is_empty());
+
+        // sequence number out of range and invalid hex in the beacon
+        let parsed = parse("MT1001999AL400C592753572BZ23433212S1723756E4706").unwrap();
+        assert_eq!(
+            validate(&parsed),
+            vec![
+                ParseError::InvalidField {
+                    field: "sequence_number",
+                    raw: "999".to_string()
+                },
+                ParseError::InvalidField {
+                    field: "beacon",
+                    raw: "400C592753572BZ".to_string()
+                },
+            ]
+        );
+
+        // longitude out of range and unknown longitude direction
+        let parsed = parse("MT1001000AL400C592753572B323433212S1813756X4706").unwrap();
+        let errors = validate(&parsed);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.contains(&ParseError::InvalidField {
+            field: "location",
+            raw: "433212S1813756-".to_string()
+        }));
+    }
+
+    #[test]
+    fn validate_short_fields() {
+        let parsed = parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+
+        let short_id = MtStructured {
+            id: DeviceId::from("1"),
+            ..parsed.clone()
+        };
+        assert_eq!(
+            validate(&short_id),
+            vec![ParseError::InvalidField {
+                field: "id",
+                raw: "1".to_string()
+            }]
+        );
+
+        let short_beacon = MtStructured {
+            beacon: "400C59".to_string(),
+            ..parsed.clone()
+        };
+        assert_eq!(
+            validate(&short_beacon),
+            vec![ParseError::InvalidField {
+                field: "beacon",
+                raw: "400C59".to_string()
+            }]
+        );
+
+        // the location is built from its fields, not sliced from the (shorter) body
+        let bad_location = MtStructured {
+            lat_degrees: Some(95),
+            ..short_beacon
+        };
+        assert!(validate(&bad_location).contains(&ParseError::InvalidField {
+            field: "location",
+            raw: "953212S1723756E".to_string()
+        }));
+    }
+
+    #[test]
+    fn same_alert() {
+        let message = "MT1001000AL400C592753572B323433212S1723756E4706";
+        let first = parse(message).unwrap();
+        let second = parse(message).unwrap();
+        assert!(second.same_alert_as(&first));
+        assert!(second.is_retransmission_of(&first));
+
+        // same alert, different signal strength
+        let weaker = MtStructured {
+            signal_strength: "11".to_string(),
+            checksum: Checksum(0),
+            ..first.clone()
+        };
+        assert!(weaker.same_alert_as(&first));
+        assert!(!weaker.is_retransmission_of(&first));
+
+        let next = MtStructured {
+            sequence_number: 1,
+            ..first.clone()
+        };
+        assert!(!next.same_alert_as(&first));
+        assert!(!next.is_retransmission_of(&first));
+
+        let other_beacon = MtStructured {
+            beacon: "401C000197572B3".to_string(),
+            ..first.clone()
+        };
+        assert!(!other_beacon.same_alert_as(&first));
+        assert!(!other_beacon.is_retransmission_of(&first));
+    }
+
     #[test]
     fn unknown_location() {
         // MT1 UUU NNN T F HHHHHHHHHHHHHHH SS 11 22 33 N 444 55 66 W YYYY
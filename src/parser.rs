@@ -0,0 +1,486 @@
+//! Configurable parser
+//!
+//! [`Parser`] allows tuning how strict parsing is, e.g. to accept messages from firmware
+//! revisions that deviate from the documented format. [`crate::parse`] uses the default
+//! (strict) configuration.
+
+use std::borrow::Cow;
+
+use crate::checksum::Checksum;
+use crate::{mt_raw, mt_structured, rss, MessageKind, ParseError, ParsedMessage};
+
+/// Configurable message parser.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::parser::Parser;
+/// let parser = Parser::new().allow_extra_trailing(true);
+/// assert!(parser.parse("SS,A,123X").is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parser {
+    allow_extra_trailing: bool,
+    skip_unrecognized: bool,
+    message_type_filter: Option<Vec<MessageKind>>,
+    ascii_only: bool,
+    decode_raw: bool,
+    checksum_optional: bool,
+    normalize_hex: bool,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            allow_extra_trailing: false,
+            skip_unrecognized: false,
+            message_type_filter: None,
+            ascii_only: true,
+            decode_raw: false,
+            checksum_optional: false,
+            normalize_hex: false,
+        }
+    }
+}
+
+impl Parser {
+    /// Creates a parser with the default (strict) configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether bytes beyond the expected message length are trimmed instead of
+    /// failing with [`ParseError::SizeNotMatch`]. Defaults to `false`.
+    pub fn allow_extra_trailing(mut self, allow: bool) -> Self {
+        self.allow_extra_trailing = allow;
+        self
+    }
+
+    /// Sets whether lines without a known message prefix (`MT1`, `MT6` or `SS,`) are skipped
+    /// by line based parsing ([`Parser::parse_partial`] and [`crate::stream::StreamParser`])
+    /// instead of yielding [`ParsedMessage::Invalid`]. Defaults to `false`.
+    ///
+    /// This is useful when the MT-RX output is interleaved with other log lines.
+    pub fn skip_unrecognized(mut self, skip: bool) -> Self {
+        self.skip_unrecognized = skip;
+        self
+    }
+
+    /// Sets whether messages containing non-ASCII characters are rejected with
+    /// [`ParseError::NonAscii`] before being dispatched. Defaults to `true`.
+    pub fn ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// Sets whether the raw data of MT(6) messages is decoded while parsing, populating
+    /// [`crate::mt_raw::MtRaw::decoded`] (see
+    /// [`crate::mt_raw::MtRaw::into_mt_structured_via_decode`]). Defaults to `false`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::{parser::Parser, ParsedMessage};
+    /// let parser = Parser::new().decode_raw(true);
+    /// let parsed = parser.parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B").unwrap();
+    /// let ParsedMessage::MtRaw(raw) = parsed else { unreachable!() };
+    /// assert_eq!(raw.decoded.unwrap().beacon, "401C000197572B3");
+    /// ```
+    pub fn decode_raw(mut self, decode_raw: bool) -> Self {
+        self.decode_raw = decode_raw;
+        self
+    }
+
+    /// Sets whether MT(1) and MT(6) messages without the trailing `YYYY` checksum (43 and 45
+    /// characters long), as output by some serial modes of older firmware, are accepted.
+    /// Their checksum is set to `0000`. Defaults to `false`.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::parser::Parser;
+    /// let parser = Parser::new().checksum_optional(true);
+    /// assert!(parser.parse("MT1001000AL400C592753572B323433212S1723756E").is_ok());
+    /// ```
+    pub fn checksum_optional(mut self, optional: bool) -> Self {
+        self.checksum_optional = optional;
+        self
+    }
+
+    /// Sets whether the beacon hex code of MT(1) messages and the raw data of MT(6) messages are
+    /// uppercased, so captures differing only in case compare equal. Defaults to `false`,
+    /// keeping the message as received.
+    ///
+    /// The checksum is verified against the message as received: if it matches, it is replaced
+    /// by the checksum of the normalized message, so `verify_checksum` still holds. Messages
+    /// whose checksum does not match are left as received, so they still fail verification.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::parser::Parser;
+    /// let parser = Parser::new().normalize_hex(true);
+    /// assert_eq!(
+    ///     parser.parse("MT6001001fffe2fa00e0000cbab959db0903788c71b79FD8A"),
+    ///     parser.parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B")
+    /// );
+    /// ```
+    pub fn normalize_hex(mut self, normalize: bool) -> Self {
+        self.normalize_hex = normalize;
+        self
+    }
+
+    /// Restricts parsing to messages of the given `types`: other messages are not parsed and
+    /// [`ParsedMessage::Invalid`] is returned instead. By default every message type is parsed.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::{parser::Parser, MessageKind, ParsedMessage};
+    /// let parser = Parser::new().allowed_message_types(&[MessageKind::MtStructured]);
+    /// assert_eq!(parser.parse("SS,A,123"), Ok(ParsedMessage::Invalid("SS,A,123".to_string())));
+    /// ```
+    pub fn allowed_message_types(mut self, types: &[MessageKind]) -> Self {
+        self.message_type_filter = Some(types.to_vec());
+        self
+    }
+
+    /// Returns whether line based parsing should skip `line`, see [`Parser::skip_unrecognized`].
+    pub(crate) fn skips(&self, line: &str) -> bool {
+        self.skip_unrecognized && crate::classify(line) == MessageKind::Unknown
+    }
+
+    /// Tries to parse `message` into one of [`ParsedMessage`] types, according to this configuration.
+    /// Returns [`ParsedMessage::Invalid`] if it's an invalid message, or [`ParseError`] if parsing went wrong.
+    #[must_use = "this returns the parse result without handling it"]
+    pub fn parse(&self, message: &str) -> Result<ParsedMessage, ParseError> {
+        if self.ascii_only && !message.is_ascii() {
+            return Err(ParseError::NonAscii);
+        }
+
+        let msg = message.trim();
+        let kind = crate::detect(msg);
+
+        if self
+            .message_type_filter
+            .as_ref()
+            .is_some_and(|types| !types.contains(&kind))
+        {
+            return Ok(ParsedMessage::Invalid(message.to_string()));
+        }
+
+        let parsed = match kind {
+            MessageKind::Rss => ParsedMessage::Rss(rss::parse(self.trim(msg, rss::RSS_LEN))?),
+            MessageKind::MtStructured => {
+                let mut structured = mt_structured::parse(&self.with_checksum(
+                    self.trim(msg, mt_structured::MT1_LEN),
+                    mt_structured::MT1_LEN,
+                ))?;
+                if self.normalize_hex && structured.verify_checksum() {
+                    structured.beacon.make_ascii_uppercase();
+                    structured.checksum =
+                        Checksum(mt_raw::compute_checksum(structured.body().as_bytes()));
+                }
+                ParsedMessage::MtStructured(structured)
+            }
+            MessageKind::MtRaw => {
+                let mut raw = mt_raw::parse(
+                    &self.with_checksum(self.trim(msg, mt_raw::MT6_LEN), mt_raw::MT6_LEN),
+                )?;
+                if self.normalize_hex && raw.verify_checksum() {
+                    raw.data.make_ascii_uppercase();
+                    raw.checksum = Checksum(mt_raw::compute_checksum(raw.data.as_bytes()));
+                }
+                if self.decode_raw {
+                    raw.decoded = raw.into_mt_structured_via_decode()?;
+                }
+                ParsedMessage::MtRaw(raw)
+            }
+            MessageKind::Unknown => ParsedMessage::Invalid(message.to_string()),
+        };
+        Ok(parsed)
+    }
+
+    /// Parses the first complete message of `buf`, i.e. terminated by CR and/or LF.
+    ///
+    /// Returns the parse result and how many bytes were consumed, up to and including the
+    /// terminator (leading empty lines are consumed as well). Returns `(None, 0)` if `buf` does
+    /// not contain a complete message yet.
+    ///
+    /// Lines skipped by [`Parser::skip_unrecognized`] are consumed as well: if only skipped lines
+    /// are complete, `None` is returned with the number of bytes they take.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::parser::Parser;
+    /// let parser = Parser::new();
+    /// let (result, consumed) = parser.parse_partial("SS,A,123\r\nSS,1,");
+    /// assert!(result.unwrap().is_ok());
+    /// assert_eq!(consumed, 10);
+    /// assert_eq!(parser.parse_partial("SS,1,"), (None, 0));
+    /// ```
+    pub fn parse_partial(&self, buf: &str) -> (Option<Result<ParsedMessage, ParseError>>, usize) {
+        const TERMINATORS: [char; 2] = ['\r', '\n'];
+
+        let mut skipped = 0;
+        loop {
+            let rest = &buf[skipped..];
+            let start = skipped + rest.len() - rest.trim_start_matches(TERMINATORS).len();
+            let Some(end) = buf[start..].find(TERMINATORS).map(|i| start + i) else {
+                return (None, skipped);
+            };
+
+            let consumed = if buf[end..].starts_with("\r\n") {
+                end + 2
+            } else {
+                end + 1
+            };
+
+            if !self.skips(&buf[start..end]) {
+                return (Some(self.parse(&buf[start..end])), consumed);
+            }
+            skipped = consumed;
+        }
+    }
+
+    /// Trims `message` to `expected_len` bytes, if allowed by the configuration.
+    fn trim<'a>(&self, message: &'a str, expected_len: usize) -> &'a str {
+        if self.allow_extra_trailing {
+            message.get(..expected_len).unwrap_or(message)
+        } else {
+            message
+        }
+    }
+
+    /// Appends a `0000` checksum to `message` if it is exactly 4 bytes shorter than
+    /// `expected_len` and the configuration allows the checksum to be omitted.
+    fn with_checksum<'a>(&self, message: &'a str, expected_len: usize) -> Cow<'a, str> {
+        if self.checksum_optional && message.len() + 4 == expected_len {
+            Cow::Owned(format!("{}0000", message))
+        } else {
+            Cow::Borrowed(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_trailing() {
+        // 48 bytes
+        let message = "MT1001000AL400C592753572B323433212S1723756E4706X";
+
+        assert_eq!(
+            Parser::new().parse(message),
+            Err(ParseError::SizeNotMatch {
+                expected: 47,
+                found: 48
+            })
+        );
+
+        let tolerant = Parser::new().allow_extra_trailing(true);
+        assert_eq!(
+            tolerant.parse(message),
+            Parser::new().parse("MT1001000AL400C592753572B323433212S1723756E4706")
+        );
+        assert!(tolerant
+            .parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B0")
+            .is_ok());
+
+        // still fails if shorter than expected
+        assert!(tolerant
+            .parse("MT1001000AL400C592753572B323433212S172375")
+            .is_err());
+    }
+
+    #[test]
+    fn ascii_only() {
+        // 'é' is 2 bytes, keeping the expected length
+        let message = "MT1001000AL400C592753572B323433212S172375é4706";
+        assert_eq!(message.len(), 47);
+        assert_eq!(Parser::new().parse(message), Err(ParseError::NonAscii));
+        assert_eq!(crate::parse("SS,A,1é"), Err(ParseError::NonAscii));
+        assert!(Parser::new().ascii_only(false).parse("SS,A,1é").is_err());
+        assert!(Parser::new()
+            .ascii_only(false)
+            .parse("hello, wörld")
+            .is_ok());
+    }
+
+    #[test]
+    fn decode_raw() {
+        let message = "MT6001000FFFE2FA0062C93A9AB959E55EE7788C71B791131";
+        let Ok(ParsedMessage::MtRaw(raw)) = Parser::new().parse(message) else {
+            unreachable!()
+        };
+        assert_eq!(raw.decoded, None);
+
+        let Ok(ParsedMessage::MtRaw(raw)) = Parser::new().decode_raw(true).parse(message) else {
+            unreachable!()
+        };
+        let decoded = raw.decoded.unwrap();
+        assert_eq!(
+            decoded.position(),
+            crate::mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706")
+                .unwrap()
+                .position()
+        );
+
+        // not a first generation beacon message
+        let message = "MT6001001000E2FA00E0000CBAB959DB0903788C71B79F84B";
+        let Ok(ParsedMessage::MtRaw(raw)) = Parser::new().decode_raw(true).parse(message) else {
+            unreachable!()
+        };
+        assert_eq!(raw.decoded, None);
+    }
+
+    #[test]
+    fn message_type_filter() {
+        let alerts_only = Parser::new().allowed_message_types(&[MessageKind::MtStructured]);
+        let invalid = |m: &str| Ok(ParsedMessage::Invalid(m.to_string()));
+        assert_eq!(alerts_only.parse("SS,A,123"), invalid("SS,A,123"));
+        assert_eq!(
+            alerts_only.parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B"),
+            invalid("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B")
+        );
+        // filtered out messages are not parsed at all
+        assert_eq!(alerts_only.parse("SS,X,123"), invalid("SS,X,123"));
+
+        let message = "MT1001000AL400C592753572B323433212S1723756E4706";
+        assert_eq!(alerts_only.parse(message), crate::parse(message));
+        assert!(matches!(
+            alerts_only.parse(message),
+            Ok(ParsedMessage::MtStructured(_))
+        ));
+
+        let nothing = Parser::new().allowed_message_types(&[]);
+        assert_eq!(nothing.parse(message), invalid(message));
+    }
+
+    #[test]
+    fn partial_complete() {
+        let parser = Parser::new();
+        let (result, consumed) = parser.parse_partial("SS,A,123\r\n");
+        assert_eq!(result, Some(crate::parse("SS,A,123")));
+        assert_eq!(consumed, 10);
+
+        let (result, consumed) = parser.parse_partial("SS,A,123\n");
+        assert!(result.is_some());
+        assert_eq!(consumed, 9);
+    }
+
+    #[test]
+    fn partial_incomplete() {
+        let parser = Parser::new();
+        assert_eq!(parser.parse_partial(""), (None, 0));
+        assert_eq!(parser.parse_partial("MT1001000AL400C59"), (None, 0));
+        assert_eq!(parser.parse_partial("\r\nSS,A,1"), (None, 0));
+    }
+
+    #[test]
+    fn partial_multiple() {
+        let parser = Parser::new();
+        let buf = "\r\nMT1001000AL400C592753572B323433212S1723756E4706\r\nSS,X,123\r\nSS,A,1";
+
+        let mut offset = 0;
+        let mut results = Vec::new();
+        while let (Some(result), consumed) = parser.parse_partial(&buf[offset..]) {
+            results.push(result);
+            offset += consumed;
+        }
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(results[0], Ok(ParsedMessage::MtStructured(_))));
+        assert!(results[1].is_err());
+        assert_eq!(&buf[offset..], "SS,A,1");
+    }
+
+    #[test]
+    fn partial_skip_unrecognized() {
+        let parser = Parser::new().skip_unrecognized(true);
+        let buf = "12:00:01 capture started\r\nSS,A,123\r\n12:00:02 tick\r\nSS,1,";
+        let (result, consumed) = parser.parse_partial(buf);
+        assert_eq!(result, Some(crate::parse("SS,A,123")));
+        assert_eq!(consumed, 36);
+        // the skipped line is consumed, the incomplete message is not
+        assert_eq!(parser.parse_partial(&buf[consumed..]), (None, 15));
+        assert_eq!(parser.parse_partial("12:00:03 tick\r\n\r\n"), (None, 15));
+        assert_eq!(parser.parse_partial("\r\nSS,1,"), (None, 0));
+
+        // no recursion per skipped line
+        let junk = "junk\n".repeat(200_000);
+        let long = format!("{}SS,A,123\n", junk);
+        assert_eq!(
+            parser.parse_partial(&long),
+            (Some(crate::parse("SS,A,123")), long.len())
+        );
+        assert_eq!(parser.parse_partial(&junk), (None, junk.len()));
+
+        let (result, _) = Parser::new().parse_partial(buf);
+        assert_eq!(
+            result,
+            Some(Ok(ParsedMessage::Invalid(
+                "12:00:01 capture started".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn checksum_optional() {
+        let mt1 = "MT1001000AL400C592753572B323433212S1723756E";
+        let mt6 = "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79";
+        assert!(Parser::new().parse(mt1).is_err());
+        assert!(Parser::new().parse(mt6).is_err());
+
+        let parser = Parser::new().checksum_optional(true);
+        let Ok(ParsedMessage::MtStructured(structured)) = parser.parse(mt1) else {
+            panic!("MT1 without checksum not parsed");
+        };
+        assert_eq!(structured.beacon, "400C592753572B3");
+        assert_eq!(structured.checksum, Checksum(0));
+
+        let Ok(ParsedMessage::MtRaw(raw)) = parser.parse(mt6) else {
+            panic!("MT6 without checksum not parsed");
+        };
+        assert_eq!(raw.data, "FFFE2FA00E0000CBAB959DB0903788C71B79");
+        assert_eq!(raw.checksum, Checksum(0));
+
+        // messages with a checksum are still parsed as usual
+        let parsed = parser.parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B");
+        assert!(matches!(parsed, Ok(ParsedMessage::MtRaw(raw)) if raw.verify_checksum()));
+        assert!(parser
+            .parse("MT1001000AL400C592753572B323433212S1723756")
+            .is_err());
+    }
+
+    #[test]
+    fn normalize_hex() {
+        let parser = Parser::new().normalize_hex(true);
+        let verified = |parsed: Result<ParsedMessage, ParseError>| match parsed {
+            Ok(ParsedMessage::MtStructured(msg)) => msg.verify_checksum(),
+            Ok(ParsedMessage::MtRaw(msg)) => msg.verify_checksum(),
+            _ => false,
+        };
+
+        let mt1 = "MT1001000AL400C592753572B323433212S1723756E4706";
+        let body = "MT1001000AL400c592753572b323433212S1723756E";
+        let lowercase = format!("{}{}", body, mt_raw::checksum_hex_string(body));
+        assert!(verified(Parser::new().parse(&lowercase)));
+        assert_ne!(Parser::new().parse(&lowercase), Parser::new().parse(mt1));
+        assert_eq!(parser.parse(&lowercase), parser.parse(mt1));
+        assert_eq!(parser.parse(&lowercase), Parser::new().parse(mt1));
+        assert!(verified(parser.parse(&lowercase)));
+
+        let mt6 = "MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B";
+        let lowercase = "MT6001001fffe2fa00e0000cbab959db0903788c71b79FD8A";
+        assert!(verified(Parser::new().parse(lowercase)));
+        assert_ne!(Parser::new().parse(lowercase), Parser::new().parse(mt6));
+        assert_eq!(parser.parse(lowercase), Parser::new().parse(mt6));
+        assert!(verified(parser.parse(lowercase)));
+
+        // corrupt packets stay as received, and still fail verification
+        let corrupt = "MT6001001fffe2fa00e0000cbab959db0903788c71b79F84B";
+        assert_eq!(parser.parse(corrupt), Parser::new().parse(corrupt));
+        assert!(!verified(parser.parse(corrupt)));
+        let corrupt = "MT1001000AL400c592753572b323433212S1723756E4706";
+        assert_eq!(parser.parse(corrupt), Parser::new().parse(corrupt));
+        assert!(!verified(parser.parse(corrupt)));
+    }
+}
@@ -0,0 +1,208 @@
+//! Iterator pipelines
+//!
+//! Helpers composing message parsing with the standard iterator adapters, e.g. to extract the
+//! distress alerts of a log:
+//!
+//! ```
+//! use wte_mt_rx_parser::pipeline::{alerts, parse_iter};
+//! let log = "SS,A,123\nMT1001000AL400C592753572B323433212S1723756E4706\n";
+//! let beacons: Vec<_> = alerts(parse_iter(log.lines()).filter_map(Result::ok))
+//!     .map(|alert| alert.beacon)
+//!     .collect();
+//! assert_eq!(beacons, vec!["400C592753572B3"]);
+//! ```
+//!
+//! [`MessagePipeline`] composes the same steps for messages pushed one at a time into a
+//! [`MessageSink`].
+
+use crate::mt_structured::MtStructured;
+use crate::rss::Rss;
+use crate::sink::MessageSink;
+use crate::{ParseError, ParsedMessage};
+
+/// Parses every item of `lines` (see [`crate::parse`]), lazily.
+pub fn parse_iter<'a>(
+    lines: impl Iterator<Item = &'a str> + 'a,
+) -> impl Iterator<Item = Result<ParsedMessage, ParseError>> + 'a {
+    lines.map(crate::parse)
+}
+
+/// Keeps only the distress alerts of `iter`, i.e. MT(1) messages of type alert.
+pub fn alerts<'a>(
+    iter: impl Iterator<Item = ParsedMessage> + 'a,
+) -> impl Iterator<Item = MtStructured> + 'a {
+    iter.filter_map(|msg| match msg {
+        ParsedMessage::MtStructured(msg) if msg.message_type.is_distress() => Some(msg),
+        _ => None,
+    })
+}
+
+/// Keeps only the RSS messages of `iter`.
+pub fn rss_readings<'a>(
+    iter: impl Iterator<Item = ParsedMessage> + 'a,
+) -> impl Iterator<Item = Rss> + 'a {
+    iter.filter_map(|msg| Rss::try_from(msg).ok())
+}
+
+/// Filter step of a [`MessagePipeline`].
+type Filter = Box<dyn Fn(&ParsedMessage) -> bool>;
+
+/// Transform step of a [`MessagePipeline`].
+type Transform = Box<dyn Fn(ParsedMessage) -> ParsedMessage>;
+
+/// Message processing steps feeding a [`MessageSink`]: every message is parsed, filtered,
+/// transformed and then passed to the sink.
+///
+/// Filters run in the order they were added, and a message rejected by one is dropped without
+/// running the remaining filters or any transform. Transforms then run in the order they were
+/// added. Parse errors skip both and are passed to [`MessageSink::on_error`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::pipeline::MessagePipeline;
+/// use wte_mt_rx_parser::sink::VecSink;
+/// use wte_mt_rx_parser::MessageKind;
+/// let mut pipeline =
+///     MessagePipeline::new(VecSink::default()).filter(|msg| msg.kind() == MessageKind::Rss);
+/// pipeline.process("SS,A,123");
+/// pipeline.process("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B");
+/// assert_eq!(pipeline.sink().messages.len(), 1);
+/// ```
+pub struct MessagePipeline<S: MessageSink> {
+    sink: S,
+    filters: Vec<Filter>,
+    transforms: Vec<Transform>,
+}
+
+impl<S: MessageSink> MessagePipeline<S> {
+    /// Creates a pipeline passing every parsed message to `sink` as is.
+    pub fn new(sink: S) -> Self {
+        MessagePipeline {
+            sink,
+            filters: Vec::new(),
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Adds a filter step: only messages for which `f` returns `true` go further.
+    pub fn filter(mut self, f: impl Fn(&ParsedMessage) -> bool + 'static) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Adds a transform step, replacing every message that passed the filters by `f(msg)`.
+    pub fn transform(mut self, f: impl Fn(ParsedMessage) -> ParsedMessage + 'static) -> Self {
+        self.transforms.push(Box::new(f));
+        self
+    }
+
+    /// Parses `message` (see [`crate::parse`]) and runs it through the pipeline.
+    pub fn process(&mut self, message: &str) {
+        let msg = match crate::parse(message) {
+            Ok(msg) => msg,
+            Err(err) => return self.sink.on_error(err),
+        };
+        if !self.filters.iter().all(|f| f(&msg)) {
+            return;
+        }
+
+        let msg = self.transforms.iter().fold(msg, |msg, f| f(msg));
+        self.sink.on_message(msg);
+    }
+
+    /// Returns the sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Returns the sink, consuming the pipeline.
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::sink::{CountingSink, VecSink};
+    use crate::MessageKind;
+
+    const LOG: &str = "MT1001000AL400C592753572B323433212S1723756E4706\n\
+                       SS,A,123\n\
+                       MT1001001TL400C000000FFBFF23433212S1723756E4706\n\
+                       garbage\n\
+                       MT1001002ALC009D650281540123433212S1723756E4706\n\
+                       SS,X,123\n\
+                       SS,1,042\n";
+
+    #[test]
+    fn chained() {
+        let beacons: Vec<_> = alerts(parse_iter(LOG.lines()).filter_map(Result::ok))
+            .map(|alert| alert.beacon)
+            .collect();
+        assert_eq!(beacons, vec!["400C592753572B3", "C009D6502815401"]);
+
+        let readings: Vec<_> = rss_readings(parse_iter(LOG.lines()).filter_map(Result::ok))
+            .map(|rss| rss.nnn)
+            .collect();
+        assert_eq!(readings, vec![123, 42]);
+
+        assert_eq!(parse_iter(LOG.lines()).filter(Result::is_err).count(), 1);
+    }
+
+    #[test]
+    fn message_pipeline() {
+        let is_alert = |msg: &ParsedMessage| match msg {
+            ParsedMessage::MtStructured(msg) => msg.message_type.is_distress(),
+            _ => false,
+        };
+        let uppercase_beacon = |msg| match msg {
+            ParsedMessage::MtStructured(mut msg) => {
+                msg.beacon.make_ascii_uppercase();
+                ParsedMessage::MtStructured(msg)
+            }
+            msg => msg,
+        };
+
+        let mut pipeline = MessagePipeline::new(CountingSink::default())
+            .filter(is_alert)
+            .transform(uppercase_beacon);
+        LOG.lines().for_each(|line| pipeline.process(line));
+
+        let counts = pipeline.into_sink();
+        assert_eq!(counts.count(MessageKind::MtStructured), 2);
+        assert_eq!(counts.count(MessageKind::Rss), 0);
+        assert_eq!(counts.count(MessageKind::Unknown), 0);
+        assert_eq!(counts.errors(), 1);
+
+        let mut pipeline = MessagePipeline::new(VecSink::default())
+            .filter(is_alert)
+            .transform(uppercase_beacon);
+        pipeline.process("MT1001000AL400c592753572b323433212S1723756E4706");
+        let ParsedMessage::MtStructured(alert) = &pipeline.sink().messages[0] else {
+            unreachable!()
+        };
+        assert_eq!(alert.beacon, "400C592753572B3");
+    }
+
+    #[test]
+    fn message_pipeline_short_circuits() {
+        let transformed = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&transformed);
+        let mut pipeline = MessagePipeline::new(VecSink::default())
+            .filter(|msg| msg.kind() == MessageKind::Rss)
+            .filter(|_| panic!("second filter must not run"))
+            .transform(move |msg| {
+                counter.set(counter.get() + 1);
+                msg
+            });
+
+        pipeline.process("MT1001000AL400C592753572B323433212S1723756E4706");
+        pipeline.process("garbage");
+        assert_eq!(transformed.get(), 0);
+        assert!(pipeline.sink().messages.is_empty());
+    }
+}
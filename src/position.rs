@@ -0,0 +1,61 @@
+//! Geographic positions
+//!
+//! Positions are expressed as WGS-84 decimal degrees, negative for South and West.
+
+/// Mean Earth radius, in meters.
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// Represents a geographic position in decimal degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    /// Latitude in decimal degrees, negative for South.
+    pub latitude: f64,
+
+    /// Longitude in decimal degrees, negative for West.
+    pub longitude: f64,
+}
+
+impl Position {
+    /// Creates a new position from decimal `latitude` and `longitude`.
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Position {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Returns the great-circle distance to `other` in meters, using the haversine formula on
+    /// a spherical Earth (accurate to about 0.5%).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::position::Position;
+    /// let distance = Position::new(0.0, 0.0).distance_meters(&Position::new(0.0, 1.0));
+    /// assert!((distance - 111_195.0).abs() < 1.0);
+    /// ```
+    pub fn distance_meters(&self, other: &Position) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_long = (other.longitude - self.longitude).to_radians();
+
+        let a =
+            (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_long / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().asin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        // Christchurch to Wellington airports, ~304 km
+        let christchurch = Position::new(-43.4894, 172.5322);
+        let wellington = Position::new(-41.3272, 174.8053);
+        let distance = christchurch.distance_meters(&wellington);
+        assert!((distance - 304_000.0).abs() < 2_000.0, "{}", distance);
+        assert_eq!(wellington.distance_meters(&christchurch), distance);
+        assert_eq!(christchurch.distance_meters(&christchurch), 0.0);
+    }
+}
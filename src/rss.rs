@@ -10,8 +10,19 @@
 //! the alert setting.
 //! - `SS,A,NNN<CR>`
 
+use std::collections::VecDeque;
+
 use crate::ParseError;
 
+/// Length of a RSS message, without the trailing `<CR>`.
+pub const RSS_LEN: usize = 8;
+
+/// Lowest `NNN` value.
+pub const MIN_NNN: u8 = 0;
+
+/// Highest `NNN` value.
+pub const MAX_NNN: u8 = 255;
+
 /// Represents a RSS “Received Signal Strength" message type.
 #[derive(Clone, Debug, PartialEq)]
 pub enum RssType {
@@ -21,6 +32,32 @@ pub enum RssType {
     Alert,
 }
 
+impl RssType {
+    /// Returns the type character used on the wire (`"1"` or `"A"`).
+    pub fn prefix_string(&self) -> &'static str {
+        match self {
+            RssType::Frequency => "1",
+            RssType::Alert => "A",
+        }
+    }
+
+    /// Returns the type matching the wire type character `c`, or `None` if unknown.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::rss::RssType;
+    /// assert_eq!(RssType::from_prefix_char('A'), Some(RssType::Alert));
+    /// assert_eq!(RssType::from_prefix_char('X'), None);
+    /// ```
+    pub fn from_prefix_char(c: char) -> Option<RssType> {
+        match c {
+            '1' => Some(RssType::Frequency),
+            'A' => Some(RssType::Alert),
+            _ => None,
+        }
+    }
+}
+
 /// Represents a RSS “Received Signal Strength" message.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Rss {
@@ -31,6 +68,106 @@ pub struct Rss {
     pub nnn: u8,
 }
 
+impl Rss {
+    /// Returns the message in its wire format (`SS,X,NNN`), without the trailing `<CR>`.
+    pub fn encode(&self) -> String {
+        format!("SS,{},{:03}", self.rss_type.prefix_string(), self.nnn)
+    }
+
+    /// Returns whether `nnn` is within [`MIN_NNN`] and [`MAX_NNN`], which always holds for a
+    /// `u8`. Use [`Rss::saturating_nnn_from_i16`] to normalize wider values.
+    pub fn is_valid_nnn(nnn: u8) -> bool {
+        (MIN_NNN..=MAX_NNN).contains(&nnn)
+    }
+
+    /// Returns `n` clamped to [`MIN_NNN`] and [`MAX_NNN`].
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::rss::Rss;
+    /// assert_eq!(Rss::saturating_nnn_from_i16(-5), 0);
+    /// assert_eq!(Rss::saturating_nnn_from_i16(300), 255);
+    /// ```
+    pub fn saturating_nnn_from_i16(n: i16) -> u8 {
+        n.clamp(MIN_NNN as i16, MAX_NNN as i16) as u8
+    }
+
+    /// Returns the approximate signal strength in dBm (`-130 + (NNN / 2)`).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::rss;
+    /// assert_eq!(rss::parse("SS,1,123").unwrap().dbm(), -68.5);
+    /// ```
+    pub fn dbm(&self) -> f64 {
+        -130.0 + self.nnn as f64 / 2.0
+    }
+}
+
+/// Rolling average of the signal strength of the last RSS readings, e.g. for a signal meter.
+///
+/// Only [`RssType::Frequency`] readings are averaged unless alerts are included with
+/// [`RssAverager::include_alerts`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::rss::{self, RssAverager};
+/// let mut averager = RssAverager::new(4);
+/// for msg in ["SS,1,100", "SS,1,120", "SS,A,250"] {
+///     averager.push(&rss::parse(msg).unwrap());
+/// }
+/// assert_eq!(averager.average_dbm(), Some(-75.0));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RssAverager {
+    /// Maximum number of readings averaged.
+    window: usize,
+
+    /// Whether alert readings are averaged too.
+    include_alerts: bool,
+
+    /// Signal strength of the averaged readings in dBm, oldest first.
+    values: VecDeque<f64>,
+}
+
+impl RssAverager {
+    /// Creates an averager over the last `window` readings.
+    pub fn new(window: usize) -> Self {
+        RssAverager {
+            window,
+            include_alerts: false,
+            values: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Sets whether [`RssType::Alert`] readings are averaged too. Defaults to `false`.
+    pub fn include_alerts(mut self, include_alerts: bool) -> Self {
+        self.include_alerts = include_alerts;
+        self
+    }
+
+    /// Adds `rss` to the average, dropping the oldest reading if the window is full.
+    pub fn push(&mut self, rss: &Rss) {
+        if rss.rss_type == RssType::Alert && !self.include_alerts {
+            return;
+        }
+
+        self.values.push_back(rss.dbm());
+        while self.values.len() > self.window {
+            self.values.pop_front();
+        }
+    }
+
+    /// Returns the mean signal strength of the readings in the window, in dBm, or `None` if
+    /// there are none.
+    pub fn average_dbm(&self) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        Some(self.values.iter().sum::<f64>() / self.values.len() as f64)
+    }
+}
+
 /// Returns whether `message` is a valid RSS message.
 ///
 /// ## Examples
@@ -38,23 +175,31 @@ pub struct Rss {
 /// use wte_mt_rx_parser::rss;
 /// println!("is it rss? {}", rss::is_rss("SS,A,123"));
 /// ```
+#[must_use]
 pub fn is_rss(message: &str) -> bool {
-    return message.starts_with("SS,");
+    message.starts_with("SS,")
 }
 
 /// Tries to parse a RSS `message`.
 ///
+/// Messages that are not 8 characters long fail with [`ParseError::SizeNotMatch`], while a
+/// 3 digit `NNN` above 255 fails with [`ParseError::RssOutOfRange`].
+///
 /// ## Examples
 /// ```
 /// use wte_mt_rx_parser::rss;
 /// let parsed = rss::parse("SS,A,123").unwrap();
 /// println!("parsed: {:?}", parsed);
 /// ```
+#[must_use = "this returns the parse result without handling it"]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(message), ret, err(level = "warn"))
+)]
 pub fn parse(message: &str) -> Result<Rss, ParseError> {
     // 01 2 3 4 567
     // SS , X , NNN
 
-    const RSS_LEN: usize = 8;
     if message.len() != RSS_LEN {
         return Err(ParseError::SizeNotMatch {
             expected: RSS_LEN,
@@ -62,18 +207,197 @@ pub fn parse(message: &str) -> Result<Rss, ParseError> {
         });
     }
 
-    let x = message.as_bytes()[3];
-    let nnn = message[5..8].parse::<u8>()?;
+    let x = message.as_bytes()[3] as char;
+    let nnn = message[5..8].parse::<u16>()?;
+    let nnn = u8::try_from(nnn).map_err(|_| ParseError::RssOutOfRange { found: nnn })?;
+    let rss_type = RssType::from_prefix_char(x).ok_or(ParseError::InvalidRssType { found: x })?;
+
+    Ok(Rss { rss_type, nnn })
+}
+
+/// Tries to parse a RSS `message` from its bytes, e.g. straight from a serial port buffer.
+///
+/// Accepts and rejects exactly the same messages as [`parse`], with the same errors, but
+/// converts the usual 3 digit `NNN` without going through [`str::parse`]. As with [`parse`],
+/// the `SS,` prefix is not checked, see [`is_rss`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::rss;
+/// assert_eq!(rss::parse_rss_fast(b"SS,A,123"), rss::parse("SS,A,123"));
+/// ```
+pub fn parse_rss_fast(message: &[u8]) -> Result<Rss, ParseError> {
+    let &[_, _, _, x, _, a, b, c] = message else {
+        return Err(ParseError::SizeNotMatch {
+            expected: RSS_LEN,
+            found: message.len(),
+        });
+    };
+
+    let nnn = if a.is_ascii_digit() && b.is_ascii_digit() && c.is_ascii_digit() {
+        (a - b'0') as u16 * 100 + (b - b'0') as u16 * 10 + (c - b'0') as u16
+    } else {
+        // signs and errors, reported as by `parse`
+        std::str::from_utf8(&message[5..8])?.parse::<u16>()?
+    };
+    let nnn = u8::try_from(nnn).map_err(|_| ParseError::RssOutOfRange { found: nnn })?;
+    let x = x as char;
+    let rss_type = RssType::from_prefix_char(x).ok_or(ParseError::InvalidRssType { found: x })?;
+
+    Ok(Rss { rss_type, nnn })
+}
+
+/// Returns only the `NNN` value of a RSS `message`, without constructing a [`Rss`].
+///
+/// This is a fast path for monitoring loops. For messages starting with `SS,` it accepts exactly
+/// the same messages as [`parse`], returning `None` for any other input.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::rss;
+/// assert_eq!(rss::parse_nnn_only("SS,A,123"), Some(123));
+/// assert_eq!(rss::parse_nnn_only("SS,X,123"), None);
+/// ```
+#[must_use]
+pub fn parse_nnn_only(message: &str) -> Option<u8> {
+    let &[b'S', b'S', b',', b'A' | b'1', _, a, b, c] = message.as_bytes() else {
+        return None;
+    };
+
+    if a.is_ascii_digit() && b.is_ascii_digit() && c.is_ascii_digit() {
+        let nnn = (a - b'0') as u16 * 100 + (b - b'0') as u16 * 10 + (c - b'0') as u16;
+        u8::try_from(nnn).ok()
+    } else {
+        // signs and errors, handled as by `parse`
+        message.get(5..8)?.parse::<u8>().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_round_trip() {
+        for rss_type in [RssType::Frequency, RssType::Alert] {
+            let c = rss_type.prefix_string().chars().next().unwrap();
+            assert_eq!(RssType::from_prefix_char(c), Some(rss_type));
+        }
+        assert_eq!(RssType::from_prefix_char('2'), None);
+    }
+
+    #[test]
+    fn nnn_bounds() {
+        assert!(Rss::is_valid_nnn(MIN_NNN));
+        assert!(Rss::is_valid_nnn(MAX_NNN));
+
+        assert_eq!(Rss::saturating_nnn_from_i16(i16::MIN), 0);
+        assert_eq!(Rss::saturating_nnn_from_i16(-1), 0);
+        assert_eq!(Rss::saturating_nnn_from_i16(0), 0);
+        assert_eq!(Rss::saturating_nnn_from_i16(123), 123);
+        assert_eq!(Rss::saturating_nnn_from_i16(255), 255);
+        assert_eq!(Rss::saturating_nnn_from_i16(256), 255);
+        assert_eq!(Rss::saturating_nnn_from_i16(i16::MAX), 255);
+    }
+
+    #[test]
+    fn rolling_average() {
+        let mut averager = RssAverager::new(3);
+        assert_eq!(averager.average_dbm(), None);
+
+        for msg in ["SS,1,000", "SS,1,020", "SS,A,200", "SS,1,040", "SS,1,060"] {
+            averager.push(&parse(msg).unwrap());
+        }
+        // last 3 frequency readings: -120, -110 and -100 dBm
+        assert_eq!(averager.average_dbm(), Some(-110.0));
+
+        let mut averager = RssAverager::new(3).include_alerts(true);
+        for msg in ["SS,1,000", "SS,A,200"] {
+            averager.push(&parse(msg).unwrap());
+        }
+        assert_eq!(averager.average_dbm(), Some(-80.0));
+
+        let mut averager = RssAverager::new(0);
+        averager.push(&parse("SS,1,100").unwrap());
+        assert_eq!(averager.average_dbm(), None);
+    }
+
+    #[test]
+    fn length_and_range_errors() {
+        assert_eq!(
+            parse("SS,A,1234"),
+            Err(ParseError::SizeNotMatch {
+                expected: RSS_LEN,
+                found: 9
+            })
+        );
+        assert_eq!(
+            parse("SS,A,256"),
+            Err(ParseError::RssOutOfRange { found: 256 })
+        );
+        assert_eq!(
+            parse("SS,1,999"),
+            Err(ParseError::RssOutOfRange { found: 999 })
+        );
+        assert_eq!(parse("SS,A,255").map(|rss| rss.nnn), Ok(255));
+    }
+
+    #[test]
+    fn fast_matches_parse() {
+        let types = ['A', '1', 'X'];
+        let samples: Vec<String> = (0..300)
+            .map(|i| format!("SS,{},{:03}", types[i % types.len()], i * 7 % 1000))
+            .chain(
+                [
+                    "SS,A,12",
+                    "SS,A,1234",
+                    "SS,1,-12",
+                    "SS,1,+12",
+                    "SS,1,+1a",
+                    "SS,A, 12",
+                    "XX,1,123",
+                    "SS,1,12é",
+                    "",
+                ]
+                .map(String::from),
+            )
+            .collect();
+
+        for sample in samples {
+            assert_eq!(
+                parse_rss_fast(sample.as_bytes()),
+                parse(&sample),
+                "{}",
+                sample
+            );
+        }
+
+        // not UTF-8, which `parse` can not be given
+        assert!(matches!(
+            parse_rss_fast(b"SS,1,\xff12"),
+            Err(ParseError::Utf8Error(_))
+        ));
+    }
+
+    #[test]
+    fn nnn_only_matches_parse() {
+        let types = ['A', '1', 'X', '2'];
+        let samples: Vec<String> = (0..100)
+            .map(|i| format!("SS,{},{:03}", types[i % types.len()], i * 3))
+            .chain(["SS,A,12", "SS,A,1234", "SS,1,-12", "SS,1,+12"].map(String::from))
+            .collect();
+        assert!(samples.len() >= 100);
+
+        for sample in samples {
+            assert_eq!(
+                parse_nnn_only(&sample),
+                parse(&sample).ok().map(|rss| rss.nnn),
+                "{}",
+                sample
+            );
+        }
 
-    match x {
-        b'A' => Ok(Rss {
-            nnn,
-            rss_type: RssType::Alert,
-        }),
-        b'1' => Ok(Rss {
-            nnn,
-            rss_type: RssType::Frequency,
-        }),
-        _ => Err(ParseError::Invalid),
+        assert_eq!(parse_nnn_only("XX,1,123"), None);
+        assert_eq!(parse_nnn_only("SS,Aé12"), None);
     }
 }
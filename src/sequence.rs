@@ -0,0 +1,220 @@
+//! MT packet sequence numbers
+//!
+//! Both MT(1) and MT(6) packets carry a 3 decimal digit cycling sequence number (`NNN`) from 000
+//! to 511, incremented after each new test or distress message.
+
+use std::fmt;
+
+use crate::mt_structured::MAX_SEQUENCE_NUMBER;
+
+/// A MT packet sequence number, guaranteed to be at most [`MAX_SEQUENCE_NUMBER`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::sequence::SequenceNumber;
+/// let seq = SequenceNumber::new(7).unwrap();
+/// assert_eq!(seq.to_string(), "007");
+/// assert!(SequenceNumber::new(512).is_none());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SequenceNumber(u16);
+
+impl SequenceNumber {
+    /// Returns `value` as a sequence number, or `None` if above [`MAX_SEQUENCE_NUMBER`].
+    pub fn new(value: u16) -> Option<SequenceNumber> {
+        (value as usize <= MAX_SEQUENCE_NUMBER).then_some(SequenceNumber(value))
+    }
+
+    /// Returns the sequence number value.
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+
+    /// Returns an endless iterator over the sequence numbers from `start`, cycling back to `0`
+    /// after [`MAX_SEQUENCE_NUMBER`].
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::sequence::SequenceNumber;
+    /// let start = SequenceNumber::new(511).unwrap();
+    /// let seqs: Vec<u16> = SequenceNumber::iter_from(start).take(2).map(|s| s.get()).collect();
+    /// assert_eq!(seqs, vec![511, 0]);
+    /// ```
+    pub fn iter_from(start: SequenceNumber) -> SequenceIter {
+        SequenceIter { current: start }
+    }
+}
+
+/// Endless iterator over cycling sequence numbers, see [`SequenceNumber::iter_from`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SequenceIter {
+    /// Next sequence number to yield.
+    current: SequenceNumber,
+}
+
+impl Iterator for SequenceIter {
+    type Item = SequenceNumber;
+
+    fn next(&mut self) -> Option<SequenceNumber> {
+        let seq = self.current;
+        self.current = SequenceNumber((seq.0 + 1) % (MAX_SEQUENCE_NUMBER as u16 + 1));
+        Some(seq)
+    }
+}
+
+impl fmt::Display for SequenceNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:03}", self.0)
+    }
+}
+
+/// Default largest number of sequence numbers [`SequenceMonitor`] accepts to be skipped over
+/// when the sequence cycles back to `0`.
+pub const DEFAULT_MAX_WRAP_GAP: u16 = 16;
+
+/// How a sequence number relates to the previously observed one, see [`SequenceMonitor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequenceEvent {
+    /// First sequence number, or higher than the previous one.
+    Normal,
+
+    /// Lower than the previous one, after plausibly cycling past [`MAX_SEQUENCE_NUMBER`].
+    Wrapped,
+
+    /// Lower than the previous one without a plausible wrap, e.g. after a receiver restart.
+    Reset,
+
+    /// Same as the previous one.
+    Duplicate,
+}
+
+/// Tracks the sequence numbers of a single MT-RX to detect restarts and duplicated messages.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::sequence::{SequenceEvent, SequenceMonitor, SequenceNumber};
+/// let mut monitor = SequenceMonitor::new();
+/// let seq = |n| SequenceNumber::new(n).unwrap();
+/// assert_eq!(monitor.observe(seq(510)), SequenceEvent::Normal);
+/// assert_eq!(monitor.observe(seq(1)), SequenceEvent::Wrapped);
+/// assert_eq!(monitor.observe(seq(1)), SequenceEvent::Duplicate);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SequenceMonitor {
+    last: Option<SequenceNumber>,
+    max_wrap_gap: u16,
+}
+
+impl Default for SequenceMonitor {
+    fn default() -> Self {
+        SequenceMonitor {
+            last: None,
+            max_wrap_gap: DEFAULT_MAX_WRAP_GAP,
+        }
+    }
+}
+
+impl SequenceMonitor {
+    /// Creates a monitor that has not observed any sequence number yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many sequence numbers may be skipped over when cycling back to `0` for a lower
+    /// sequence number to count as [`SequenceEvent::Wrapped`] rather than
+    /// [`SequenceEvent::Reset`]. Defaults to [`DEFAULT_MAX_WRAP_GAP`].
+    pub fn max_wrap_gap(mut self, max_wrap_gap: u16) -> Self {
+        self.max_wrap_gap = max_wrap_gap;
+        self
+    }
+
+    /// Records `seq`, returning how it relates to the previously observed sequence number.
+    pub fn observe(&mut self, seq: SequenceNumber) -> SequenceEvent {
+        let event = match self.last {
+            None => SequenceEvent::Normal,
+            Some(last) if seq == last => SequenceEvent::Duplicate,
+            Some(last) if seq > last => SequenceEvent::Normal,
+            Some(last) => {
+                // sequence numbers skipped between `last` and `seq` going through 511 -> 0
+                let skipped = (MAX_SEQUENCE_NUMBER as u16 - last.0) + seq.0;
+                if skipped <= self.max_wrap_gap {
+                    SequenceEvent::Wrapped
+                } else {
+                    SequenceEvent::Reset
+                }
+            }
+        };
+        self.last = Some(seq);
+        event
+    }
+}
+
+/// Compares against the 3 decimal digit representation, e.g. `"007"`.
+impl PartialEq<&str> for SequenceNumber {
+    fn eq(&self, other: &&str) -> bool {
+        other.len() == 3 && other.bytes().all(|b| b.is_ascii_digit()) && other.parse() == Ok(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert_eq!(SequenceNumber::new(0).map(|s| s.get()), Some(0));
+        assert_eq!(SequenceNumber::new(511).map(|s| s.get()), Some(511));
+        assert_eq!(SequenceNumber::new(512), None);
+        assert_eq!(SequenceNumber::new(511).unwrap().to_string(), "511");
+    }
+
+    #[test]
+    fn iter_wraps() {
+        let seqs: Vec<u16> = SequenceNumber::iter_from(SequenceNumber::new(510).unwrap())
+            .take(5)
+            .map(|s| s.get())
+            .collect();
+        assert_eq!(seqs, vec![510, 511, 0, 1, 2]);
+
+        let mut iter = SequenceNumber::iter_from(SequenceNumber::default());
+        assert_eq!(iter.nth(512), SequenceNumber::new(0));
+    }
+
+    #[test]
+    fn compare_with_str() {
+        let seq = SequenceNumber::new(7).unwrap();
+        assert!(seq == "007");
+        assert!(seq != "7");
+        assert!(seq != "008");
+        assert!(seq != "+07");
+    }
+
+    #[test]
+    fn monitor_events() {
+        let seq = |n| SequenceNumber::new(n).unwrap();
+        let mut monitor = SequenceMonitor::new();
+
+        assert_eq!(monitor.observe(seq(500)), SequenceEvent::Normal);
+        assert_eq!(monitor.observe(seq(501)), SequenceEvent::Normal);
+        assert_eq!(monitor.observe(seq(505)), SequenceEvent::Normal);
+        assert_eq!(monitor.observe(seq(505)), SequenceEvent::Duplicate);
+        assert_eq!(monitor.observe(seq(511)), SequenceEvent::Normal);
+        assert_eq!(monitor.observe(seq(0)), SequenceEvent::Wrapped);
+        assert_eq!(monitor.observe(seq(1)), SequenceEvent::Normal);
+        assert_eq!(monitor.observe(seq(200)), SequenceEvent::Normal);
+        assert_eq!(monitor.observe(seq(0)), SequenceEvent::Reset);
+
+        // 511 -> 16 skips 0..=15
+        let mut monitor = SequenceMonitor::new();
+        monitor.observe(seq(511));
+        assert_eq!(monitor.observe(seq(16)), SequenceEvent::Wrapped);
+        monitor.observe(seq(511));
+        assert_eq!(monitor.observe(seq(17)), SequenceEvent::Reset);
+
+        let mut monitor = SequenceMonitor::new().max_wrap_gap(0);
+        monitor.observe(seq(510));
+        assert_eq!(monitor.observe(seq(0)), SequenceEvent::Reset);
+        monitor.observe(seq(511));
+        assert_eq!(monitor.observe(seq(0)), SequenceEvent::Wrapped);
+    }
+}
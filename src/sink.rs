@@ -0,0 +1,321 @@
+//! Message sinks
+//!
+//! A [`MessageSink`] consumes parsed messages and parse errors, decoupling message processing
+//! from message routing. Use [`feed_reader`] to parse every line of a reader into a sink.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use crate::{MessageKind, ParseError, ParsedMessage};
+
+/// Consumer of parsed messages.
+pub trait MessageSink {
+    /// Called for every successfully parsed message (including [`ParsedMessage::Invalid`]).
+    fn on_message(&mut self, msg: ParsedMessage);
+
+    /// Called for every message that failed to parse.
+    fn on_error(&mut self, err: ParseError);
+
+    /// Called once the input is exhausted.
+    fn on_eof(&mut self) {}
+}
+
+/// Parses every line of `reader` and feeds the results into `sink`, calling
+/// [`MessageSink::on_eof`] at the end.
+///
+/// ## Notes
+/// - Empty lines are skipped.
+/// - Reading stops at the first I/O error (e.g. non UTF-8 data).
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::sink::{self, VecSink};
+/// let mut sink = VecSink::default();
+/// sink::feed_reader("SS,A,123\nSS,1,123\n".as_bytes(), &mut sink);
+/// assert_eq!(sink.messages.len(), 2);
+/// ```
+pub fn feed_reader<R: BufRead, S: MessageSink>(reader: R, sink: &mut S) {
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match crate::parse(&line) {
+            Ok(msg) => sink.on_message(msg),
+            Err(err) => sink.on_error(err),
+        }
+    }
+    sink.on_eof();
+}
+
+/// Sink collecting every message and error, in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VecSink {
+    /// Parsed messages.
+    pub messages: Vec<ParsedMessage>,
+
+    /// Parse errors.
+    pub errors: Vec<ParseError>,
+
+    /// Whether [`MessageSink::on_eof`] was called.
+    pub finished: bool,
+}
+
+impl MessageSink for VecSink {
+    fn on_message(&mut self, msg: ParsedMessage) {
+        self.messages.push(msg);
+    }
+
+    fn on_error(&mut self, err: ParseError) {
+        self.errors.push(err);
+    }
+
+    fn on_eof(&mut self) {
+        self.finished = true;
+    }
+}
+
+/// Sink tallying messages by [`MessageKind`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CountingSink {
+    counts: HashMap<MessageKind, usize>,
+    errors: usize,
+}
+
+impl CountingSink {
+    /// Returns how many messages of `kind` were received.
+    pub fn count(&self, kind: MessageKind) -> usize {
+        self.counts.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Returns how many messages failed to parse.
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    /// Returns how many messages were received, including errors.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum::<usize>() + self.errors
+    }
+}
+
+impl MessageSink for CountingSink {
+    fn on_message(&mut self, msg: ParsedMessage) {
+        *self.counts.entry(msg.kind()).or_insert(0) += 1;
+    }
+
+    fn on_error(&mut self, _err: ParseError) {
+        self.errors += 1;
+    }
+}
+
+/// CSV columns written by [`CsvSink`].
+const CSV_HEADER: &str =
+    "kind,id,sequence_number,message_type,beacon,signal_strength,latitude,longitude,data,nnn,checksum";
+
+/// Sink writing a CSV row per MT(1) and MT(6) message (and optionally RSS message) to a writer.
+///
+/// The header is written before the first row, and the writer is flushed after every row.
+/// Columns not carried by a message type are left empty. Parse errors and
+/// [`ParsedMessage::Invalid`] messages are not written.
+///
+/// Writing stops at the first I/O error, which is available through [`CsvSink::error`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::sink::{self, CsvSink};
+/// let mut sink = CsvSink::new(Vec::new());
+/// sink::feed_reader("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B\n".as_bytes(), &mut sink);
+/// let csv = String::from_utf8(sink.into_inner()).unwrap();
+/// assert_eq!(csv.lines().count(), 2);
+/// ```
+#[derive(Debug)]
+pub struct CsvSink<W: Write> {
+    writer: W,
+    include_rss: bool,
+    header_written: bool,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> CsvSink<W> {
+    /// Creates a sink writing to `writer`, skipping RSS messages.
+    pub fn new(writer: W) -> Self {
+        CsvSink {
+            writer,
+            include_rss: false,
+            header_written: false,
+            error: None,
+        }
+    }
+
+    /// Sets whether RSS messages are written as well.
+    pub fn include_rss(mut self, include_rss: bool) -> Self {
+        self.include_rss = include_rss;
+        self
+    }
+
+    /// Returns the I/O error that stopped writing, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_row(&mut self, fields: &[String]) -> io::Result<()> {
+        if !self.header_written {
+            writeln!(self.writer, "{}", CSV_HEADER)?;
+            self.header_written = true;
+        }
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        writeln!(self.writer, "{}", fields.join(","))?;
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> MessageSink for CsvSink<W> {
+    fn on_message(&mut self, msg: ParsedMessage) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let position = msg.position();
+        let latitude = position.map_or(String::new(), |p| format!("{:.6}", p.latitude));
+        let longitude = position.map_or(String::new(), |p| format!("{:.6}", p.longitude));
+        let row = match msg {
+            ParsedMessage::MtStructured(msg) => [
+                "MT1".to_string(),
+                msg.id.to_string(),
+                msg.sequence_number.to_string(),
+                msg.message_type.as_char().to_string(),
+                msg.beacon,
+                msg.signal_strength,
+                latitude,
+                longitude,
+                String::new(),
+                String::new(),
+                msg.checksum.to_string(),
+            ],
+            ParsedMessage::MtRaw(msg) => [
+                "MT6".to_string(),
+                msg.id.to_string(),
+                msg.sequence_number.to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                latitude,
+                longitude,
+                msg.data,
+                String::new(),
+                msg.checksum.to_string(),
+            ],
+            ParsedMessage::Rss(msg) if self.include_rss => [
+                "RSS".to_string(),
+                String::new(),
+                String::new(),
+                msg.rss_type.prefix_string().to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                msg.nnn.to_string(),
+                String::new(),
+            ],
+            _ => return,
+        };
+
+        if let Err(err) = self.write_row(&row) {
+            self.error = Some(err);
+        }
+    }
+
+    fn on_error(&mut self, _err: ParseError) {}
+}
+
+/// Quotes `field` if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const INPUT: &str = "MT1001000AL400C592753572B323433212S1723756E4706\r\n\
+                         SS,A,123\r\n\
+                         MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B\r\n\
+                         SS,X,123\r\n\
+                         \r\n\
+                         SS,1,123\r\n";
+
+    #[test]
+    fn vec_sink_in_order() {
+        let mut sink = VecSink::default();
+        feed_reader(Cursor::new(INPUT), &mut sink);
+
+        let expected: Vec<_> = INPUT
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| crate::parse(l).ok())
+            .collect();
+        assert_eq!(sink.messages, expected);
+        assert_eq!(sink.messages.len(), 4);
+        assert_eq!(sink.errors.len(), 1);
+        assert!(sink.finished);
+    }
+
+    #[test]
+    fn counting_sink() {
+        let mut sink = CountingSink::default();
+        feed_reader(Cursor::new(INPUT), &mut sink);
+
+        assert_eq!(sink.count(MessageKind::Rss), 2);
+        assert_eq!(sink.count(MessageKind::MtStructured), 1);
+        assert_eq!(sink.count(MessageKind::MtRaw), 1);
+        assert_eq!(sink.count(MessageKind::Unknown), 0);
+        assert_eq!(sink.errors(), 1);
+        assert_eq!(sink.total(), 5);
+    }
+
+    #[test]
+    fn csv_sink() {
+        let mut sink = CsvSink::new(Vec::new());
+        feed_reader(Cursor::new(INPUT), &mut sink);
+        assert!(sink.error().is_none());
+
+        let csv = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                CSV_HEADER,
+                "MT1,001,0,A,400C592753572B3,23,-43.536667,172.632222,,,4706",
+                "MT6,001,1,,,,-43.536667,172.632222,FFFE2FA00E0000CBAB959DB0903788C71B79,,F84B",
+            ]
+        );
+
+        let mut sink = CsvSink::new(Vec::new()).include_rss(true);
+        feed_reader(Cursor::new(INPUT), &mut sink);
+        let csv = String::from_utf8(sink.into_inner()).unwrap();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.contains("\nRSS,,,A,,,,,,123,\n"));
+    }
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(csv_field("001"), "001");
+        assert_eq!(csv_field("0,1"), "\"0,1\"");
+        assert_eq!(csv_field("0\"1"), "\"0\"\"1\"");
+    }
+}
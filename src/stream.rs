@@ -0,0 +1,324 @@
+//! Incremental stream parsing
+//!
+//! [`StreamParser`] accepts bytes as they arrive (e.g. from a serial port) and yields a parse
+//! result for every complete line, terminated by CR and/or LF. [`parse_from_reader`] drives it
+//! from any [`Read`] source, e.g. a serial port or a TCP connection.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read};
+use std::net::TcpStream;
+
+use crate::parser::Parser;
+use crate::{ParseError, ParsedMessage};
+
+/// Default maximum line length, in bytes. MT-RX messages are at most 49 bytes long.
+pub const DEFAULT_MAX_LINE_LEN: usize = 256;
+
+/// Incremental parser buffering partial lines between calls to [`StreamParser::feed`].
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::stream::StreamParser;
+/// let mut stream = StreamParser::new();
+/// assert!(stream.feed(b"SS,A,1").is_empty());
+/// assert_eq!(stream.feed(b"23\r\nSS,1,").len(), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamParser {
+    parser: Parser,
+    max_line_len: usize,
+    buffer: Vec<u8>,
+    discarding: bool,
+}
+
+impl Default for StreamParser {
+    fn default() -> Self {
+        StreamParser::with_parser(Parser::default())
+    }
+}
+
+impl StreamParser {
+    /// Creates a stream parser using the default [`Parser`] configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a stream parser parsing every line with `parser`.
+    pub fn with_parser(parser: Parser) -> Self {
+        StreamParser {
+            parser,
+            max_line_len: DEFAULT_MAX_LINE_LEN,
+            buffer: Vec::new(),
+            discarding: false,
+        }
+    }
+
+    /// Sets how many bytes a line may buffer before it is discarded with
+    /// [`ParseError::LineTooLong`]. Defaults to [`DEFAULT_MAX_LINE_LEN`].
+    pub fn max_line_len(mut self, max_line_len: usize) -> Self {
+        self.max_line_len = max_line_len;
+        self
+    }
+
+    /// Appends `bytes` to the stream, returning the parse result of every line completed by them.
+    ///
+    /// ## Notes
+    /// - Empty lines are skipped, as are unrecognized lines if configured
+    ///   (see [`Parser::skip_unrecognized`]).
+    /// - Lines that are not valid UTF-8 yield [`ParseError::Utf8Error`].
+    /// - When a line exceeds the maximum line length, the buffered bytes are discarded and
+    ///   [`ParseError::LineTooLong`] is yielded once. The rest of that line is dropped up to
+    ///   the next terminator, where parsing resynchronizes.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Result<ParsedMessage, ParseError>> {
+        let mut results = Vec::new();
+        for &byte in bytes {
+            if byte == b'\r' || byte == b'\n' {
+                if self.discarding {
+                    self.discarding = false;
+                } else if !self.buffer.is_empty() {
+                    results.extend(self.parse_line());
+                }
+            } else if !self.discarding {
+                self.buffer.push(byte);
+                if self.buffer.len() > self.max_line_len {
+                    self.buffer.clear();
+                    self.discarding = true;
+                    results.push(Err(ParseError::LineTooLong {
+                        max: self.max_line_len,
+                    }));
+                }
+            }
+        }
+        results
+    }
+
+    /// Returns how many bytes of an incomplete line are buffered.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Discards any incomplete line, e.g. after a period of silence on the stream.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.discarding = false;
+    }
+
+    fn parse_line(&mut self) -> Option<Result<ParsedMessage, ParseError>> {
+        let line = std::mem::take(&mut self.buffer);
+        let line = match std::str::from_utf8(&line) {
+            Ok(line) => line,
+            Err(err) => return Some(Err(err.into())),
+        };
+        if self.parser.skips(line) {
+            return None;
+        }
+        Some(self.parser.parse(line))
+    }
+}
+
+/// Lazily parses the lines read from `reader`, see [`StreamParser::feed`].
+///
+/// The input is read through a [`BufReader`], so it does not need to be buffered by the caller.
+/// A final line without terminator is parsed at end of input. A read error yields
+/// [`ParseError::Io`] and ends the iteration.
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::stream;
+/// let input = std::io::Cursor::new("SS,A,123\rSS,1,042\r");
+/// let results: Vec<_> = stream::parse_from_reader(input).collect();
+/// assert_eq!(results.len(), 2);
+/// ```
+pub fn parse_from_reader<R: Read>(
+    reader: R,
+) -> impl Iterator<Item = Result<ParsedMessage, ParseError>> {
+    ReaderParser {
+        reader: BufReader::new(reader),
+        stream: StreamParser::new(),
+        pending: VecDeque::new(),
+        done: false,
+    }
+}
+
+/// Connects to `addr` (e.g. a serial to TCP bridge) and lazily parses the lines received, see
+/// [`parse_from_reader`].
+pub fn parse_from_tcp(
+    addr: &str,
+) -> Result<impl Iterator<Item = Result<ParsedMessage, ParseError>>, io::Error> {
+    TcpStream::connect(addr).map(parse_from_reader)
+}
+
+/// Iterator returned by [`parse_from_reader`].
+struct ReaderParser<R> {
+    reader: BufReader<R>,
+    stream: StreamParser,
+    pending: VecDeque<Result<ParsedMessage, ParseError>>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for ReaderParser<R> {
+    type Item = Result<ParsedMessage, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.pending.pop_front() {
+                return Some(result);
+            }
+            if self.done {
+                return None;
+            }
+
+            match self.reader.fill_buf() {
+                Ok([]) => {
+                    self.done = true;
+                    // terminate the last line
+                    self.pending.extend(self.stream.feed(b"\n"));
+                }
+                Ok(bytes) => {
+                    let len = bytes.len();
+                    self.pending.extend(self.stream.feed(bytes));
+                    self.reader.consume(len);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(ParseError::Io { kind: err.kind() }));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn split_lines() {
+        let mut stream = StreamParser::new();
+        let input = b"MT1001000AL400C592753572B323433212S1723756E4706\r\nSS,A,123\r\n\r\nSS,X,1";
+
+        let mut results = Vec::new();
+        for chunk in input.chunks(5) {
+            results.extend(stream.feed(chunk));
+        }
+        assert_eq!(
+            results,
+            vec![
+                parse("MT1001000AL400C592753572B323433212S1723756E4706"),
+                parse("SS,A,123")
+            ]
+        );
+        assert_eq!(stream.buffered(), 6);
+
+        assert_eq!(stream.feed(b"23\n"), vec![parse("SS,X,123")]);
+        assert_eq!(stream.buffered(), 0);
+    }
+
+    #[test]
+    fn line_too_long() {
+        let mut stream = StreamParser::new();
+        assert_eq!(
+            stream.feed(&[b'X'; 300]),
+            vec![Err(ParseError::LineTooLong { max: 256 })]
+        );
+        assert_eq!(stream.buffered(), 0);
+
+        // rest of the line is dropped, then parsing resumes
+        assert!(stream.feed(b"XXXX").is_empty());
+        assert_eq!(stream.buffered(), 0);
+        assert_eq!(stream.feed(b"\r\nSS,A,123\r\n"), vec![parse("SS,A,123")]);
+
+        let mut stream = StreamParser::new().max_line_len(8);
+        assert_eq!(stream.feed(b"SS,A,123\n"), vec![parse("SS,A,123")]);
+        assert_eq!(
+            stream.feed(b"SS,A,1234\n"),
+            vec![Err(ParseError::LineTooLong { max: 8 })]
+        );
+    }
+
+    #[test]
+    fn skip_unrecognized() {
+        let input = b"2024-05-01 12:00:00 INFO capture started\r\n\
+                      MT1001000AL400C592753572B323433212S1723756E4706\r\n\
+                      2024-05-01 12:00:01 DEBUG 47 bytes\r\n\
+                      SS,A,123\r\n";
+
+        let mut stream = StreamParser::with_parser(Parser::new().skip_unrecognized(true));
+        assert_eq!(
+            stream.feed(input),
+            vec![
+                parse("MT1001000AL400C592753572B323433212S1723756E4706"),
+                parse("SS,A,123")
+            ]
+        );
+
+        let mut stream = StreamParser::new();
+        assert_eq!(stream.feed(input).len(), 4);
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut stream = StreamParser::new();
+        let results = stream.feed(b"SS,A,\xff23\nSS,A,123\n");
+        assert!(matches!(results[0], Err(ParseError::Utf8Error(_))));
+        assert_eq!(results[1], parse("SS,A,123"));
+    }
+
+    #[test]
+    fn from_reader() {
+        let input = "MT1001000AL400C592753572B323433212S1723756E4706\r\n\
+                     SS,A,123\r\n\
+                     \r\n\
+                     SS,X,123\r\n\
+                     MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B";
+        let results: Vec<_> = parse_from_reader(io::Cursor::new(input)).collect();
+        assert_eq!(
+            results,
+            vec![
+                parse("MT1001000AL400C592753572B323433212S1723756E4706"),
+                parse("SS,A,123"),
+                parse("SS,X,123"),
+                parse("MT6001001FFFE2FA00E0000CBAB959DB0903788C71B79F84B"),
+            ]
+        );
+
+        assert_eq!(parse_from_reader(io::empty()).count(), 0);
+    }
+
+    #[test]
+    fn from_reader_error() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::ConnectionReset.into())
+            }
+        }
+
+        let results: Vec<_> = parse_from_reader(Failing).collect();
+        assert_eq!(
+            results,
+            vec![Err(ParseError::Io {
+                kind: io::ErrorKind::ConnectionReset
+            })]
+        );
+    }
+
+    #[test]
+    fn from_tcp() {
+        use std::io::Write;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            socket.write_all(b"SS,A,123\rSS,1,042\r").unwrap();
+        });
+
+        let results: Vec<_> = parse_from_tcp(&addr).unwrap().collect();
+        server.join().unwrap();
+        assert_eq!(results, vec![parse("SS,A,123"), parse("SS,1,042")]);
+    }
+}
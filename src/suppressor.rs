@@ -0,0 +1,108 @@
+//! Alert suppression
+//!
+//! In high-noise environments the MT-RX may output the same alert several times in a row.
+//! [`AlertSuppressor`] drops [`MtStructured`] messages whose beacon was already accepted
+//! within a configurable time window.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::mt_structured::MtStructured;
+
+/// Drops repeated messages from the same beacon within a time window.
+#[derive(Clone, Debug)]
+pub struct AlertSuppressor {
+    /// Time window, in seconds, during which repeated messages are suppressed.
+    window_seconds: u64,
+
+    /// Last time a message was accepted, per beacon hex code.
+    seen: HashMap<String, Instant>,
+}
+
+impl AlertSuppressor {
+    /// Creates a suppressor that drops repeated beacons within `window_seconds` seconds.
+    pub fn new(window_seconds: u64) -> Self {
+        AlertSuppressor {
+            window_seconds,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Returns whether `msg` should be suppressed, because the same beacon was accepted
+    /// within the time window. Otherwise, the beacon is recorded as accepted now.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::{mt_structured, suppressor::AlertSuppressor};
+    /// let msg = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// let mut suppressor = AlertSuppressor::new(60);
+    /// assert!(!suppressor.should_suppress(&msg));
+    /// assert!(suppressor.should_suppress(&msg));
+    /// ```
+    pub fn should_suppress(&mut self, msg: &MtStructured) -> bool {
+        self.should_suppress_at(msg, Instant::now())
+    }
+
+    /// Same as [`AlertSuppressor::should_suppress`], using `now` as the current time.
+    pub fn should_suppress_at(&mut self, msg: &MtStructured, now: Instant) -> bool {
+        let window = Duration::from_secs(self.window_seconds);
+        if let Some(last) = self.seen.get(&msg.beacon) {
+            if now.saturating_duration_since(*last) < window {
+                return true;
+            }
+        }
+        self.seen.insert(msg.beacon.clone(), now);
+        false
+    }
+
+    /// Returns `msg` back if it was not suppressed, or `None` otherwise.
+    pub fn accept(&mut self, msg: MtStructured) -> Option<MtStructured> {
+        self.accept_at(msg, Instant::now())
+    }
+
+    /// Same as [`AlertSuppressor::accept`], using `now` as the current time.
+    pub fn accept_at(&mut self, msg: MtStructured, now: Instant) -> Option<MtStructured> {
+        if self.should_suppress_at(&msg, now) {
+            None
+        } else {
+            Some(msg)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_structured;
+
+    #[test]
+    fn suppress_within_window() {
+        let msg = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let mut suppressor = AlertSuppressor::new(10);
+        let start = Instant::now();
+
+        assert!(suppressor.accept_at(msg.clone(), start).is_some());
+        assert!(suppressor
+            .accept_at(msg.clone(), start + Duration::from_secs(1))
+            .is_none());
+        assert!(suppressor
+            .accept_at(msg.clone(), start + Duration::from_secs(9))
+            .is_none());
+        assert!(suppressor
+            .accept_at(msg, start + Duration::from_secs(10))
+            .is_some());
+    }
+
+    #[test]
+    fn different_beacons() {
+        let first =
+            mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+        let second =
+            mt_structured::parse("MT1001001AL400C592753572B423433212S1723756E4706").unwrap();
+        let mut suppressor = AlertSuppressor::new(10);
+
+        assert!(!suppressor.should_suppress(&first));
+        assert!(!suppressor.should_suppress(&second));
+        assert!(suppressor.should_suppress(&first));
+    }
+}
@@ -0,0 +1,71 @@
+//! Receive timestamps
+//!
+//! MT-RX messages carry no time information. [`Timestamped`] attaches the time a message was
+//! received at, using [`SystemTime`] so no date library is required.
+
+use std::time::SystemTime;
+
+use crate::{ParseError, ParsedMessage};
+
+/// A message along with the time it was received at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timestamped<T> {
+    /// When the message was received.
+    pub received_at: SystemTime,
+
+    /// The message.
+    pub message: T,
+}
+
+impl<T> Timestamped<T> {
+    /// Wraps `message`, received at `received_at`.
+    pub fn new(message: T, received_at: SystemTime) -> Self {
+        Timestamped {
+            received_at,
+            message,
+        }
+    }
+
+    /// Converts the message with `f`, keeping the receive time.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Timestamped<U> {
+        Timestamped::new(f(self.message), self.received_at)
+    }
+}
+
+/// Tries to parse `msg` (see [`crate::parse`]), stamping it as received at `now`.
+///
+/// ## Examples
+/// ```
+/// use std::time::SystemTime;
+/// use wte_mt_rx_parser::timestamped;
+/// let now = SystemTime::now();
+/// let parsed = timestamped::parse_timestamped("SS,A,123", now).unwrap();
+/// assert_eq!(parsed.received_at, now);
+/// ```
+pub fn parse_timestamped(
+    msg: &str,
+    now: SystemTime,
+) -> Result<Timestamped<ParsedMessage>, ParseError> {
+    Ok(Timestamped::new(crate::parse(msg)?, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::rss::{Rss, RssType};
+
+    #[test]
+    fn timestamped_rss() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let parsed = parse_timestamped("SS,A,123", now).unwrap();
+
+        let rss: Timestamped<Rss> = parsed.map(|msg| Rss::try_from(msg).unwrap());
+        assert_eq!(rss.received_at, now);
+        assert_eq!(rss.message.rss_type, RssType::Alert);
+        assert_eq!(rss.message.nnn, 123);
+
+        assert!(parse_timestamped("SS,X,123", now).is_err());
+    }
+}
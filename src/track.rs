@@ -0,0 +1,97 @@
+//! Beacon tracks
+//!
+//! A [`Track`] follows the reported locations of a single beacon over time, e.g. a drifting
+//! EPIRB, to estimate how far it moved and where it was last seen.
+
+use crate::mt_structured::MtStructured;
+use crate::position::Position;
+
+/// Ordered locations reported by a single beacon.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Track {
+    /// 15 Hex ID of the beacon (see [`MtStructured::beacon_15hex_id`]), or `None` for an
+    /// empty track.
+    pub beacon: Option<String>,
+
+    /// Reported locations, oldest first.
+    pub positions: Vec<Position>,
+}
+
+impl Track {
+    /// Builds the track of the beacon of the first message of `messages`, in order.
+    ///
+    /// Messages from other beacons (see [`MtStructured::same_beacon`]) and messages without a
+    /// location are ignored.
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::{mt_structured, track::Track};
+    /// let msg = mt_structured::parse("MT1001000AL400C592753572B323433212S1723756E4706").unwrap();
+    /// let track = Track::from_messages(&[msg.clone(), msg]);
+    /// assert_eq!(track.positions.len(), 2);
+    /// assert_eq!(track.length_meters(), 0.0);
+    /// ```
+    pub fn from_messages(messages: &[MtStructured]) -> Track {
+        let Some(first) = messages.first() else {
+            return Track::default();
+        };
+
+        Track {
+            beacon: Some(first.beacon_15hex_id()),
+            positions: messages
+                .iter()
+                .filter(|msg| msg.same_beacon(first))
+                .filter_map(MtStructured::position)
+                .collect(),
+        }
+    }
+
+    /// Returns the length of the path through every location, in meters
+    /// (see [`Position::distance_meters`]).
+    pub fn length_meters(&self) -> f64 {
+        self.positions
+            .windows(2)
+            .map(|pair| pair[0].distance_meters(&pair[1]))
+            .sum()
+    }
+
+    /// Returns the most recent location, if any.
+    pub fn latest(&self) -> Option<Position> {
+        self.positions.last().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mt_structured::parse;
+
+    #[test]
+    fn single_beacon() {
+        let messages: Vec<_> = [
+            "MT1001000AL400C592753572B323433000S1724500E4706",
+            // another beacon (PLB)
+            "MT1001001AL400E592753572B323413000S1724500E4706",
+            "MT1001002AL400C592753572B323431500S1724500E4706",
+            // no location
+            "MT1001003AL400C592753572B323---------------4706",
+            "MT1001004AL400C592753572B323431500S1730000E4706",
+        ]
+        .into_iter()
+        .map(|m| parse(m).unwrap())
+        .collect();
+
+        let track = Track::from_messages(&messages);
+        assert_eq!(track.beacon.as_deref(), Some("400C592752FFBFF"));
+        assert_eq!(track.positions.len(), 3);
+        assert_eq!(track.latest(), Some(Position::new(-43.25, 173.0)));
+
+        // 0.25 degree north, then 0.25 degree east
+        let length = track.length_meters();
+        assert!((length - 48_047.0).abs() < 10.0, "{}", length);
+
+        let empty = Track::from_messages(&[]);
+        assert_eq!(empty.latest(), None);
+        assert_eq!(empty.length_meters(), 0.0);
+    }
+}
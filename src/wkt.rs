@@ -0,0 +1,124 @@
+//! WKT (Well-Known Text) geometry
+//!
+//! Converts [`Position`]s to and from WKT, as used by GIS databases such as PostGIS.
+//! WKT points are written longitude first, e.g. `POINT(172.632222 -43.536667)`.
+
+use crate::position::Position;
+use crate::ParseError;
+
+impl Position {
+    /// Returns the position as a WKT point (`POINT(lon lat)`).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::position::Position;
+    /// assert_eq!(Position::new(-43.5, 172.75).to_wkt(), "POINT(172.75 -43.5)");
+    /// ```
+    pub fn to_wkt(&self) -> String {
+        format!("POINT({})", self.wkt_coordinates())
+    }
+
+    /// Tries to parse a WKT point (`POINT(lon lat)`).
+    ///
+    /// ## Examples
+    /// ```
+    /// use wte_mt_rx_parser::position::Position;
+    /// let position = Position::from_wkt("POINT(172.75 -43.5)").unwrap();
+    /// assert_eq!(position, Position::new(-43.5, 172.75));
+    /// ```
+    pub fn from_wkt(s: &str) -> Result<Position, ParseError> {
+        let s = s.trim();
+        if !s
+            .get(..5)
+            .is_some_and(|tag| tag.eq_ignore_ascii_case("POINT"))
+        {
+            return Err(ParseError::Invalid);
+        }
+
+        let coordinates = s[5..]
+            .trim_start()
+            .strip_prefix('(')
+            .and_then(|c| c.strip_suffix(')'))
+            .ok_or(ParseError::Invalid)?;
+
+        let mut values = coordinates.split_whitespace().map(str::parse::<f64>);
+        let (Some(Ok(longitude)), Some(Ok(latitude)), None) =
+            (values.next(), values.next(), values.next())
+        else {
+            return Err(ParseError::Invalid);
+        };
+
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(ParseError::Invalid);
+        }
+
+        Ok(Position::new(latitude, longitude))
+    }
+
+    fn wkt_coordinates(&self) -> String {
+        format!("{} {}", self.longitude, self.latitude)
+    }
+}
+
+/// Returns `positions` as a WKT multipoint (`MULTIPOINT((lon lat), ...)`).
+///
+/// ## Examples
+/// ```
+/// use wte_mt_rx_parser::{position::Position, wkt};
+/// let positions = [Position::new(-43.5, 172.75), Position::new(-41.0, 174.5)];
+/// assert_eq!(
+///     wkt::positions_to_wkt_multipoint(&positions),
+///     "MULTIPOINT((172.75 -43.5), (174.5 -41))"
+/// );
+/// ```
+pub fn positions_to_wkt_multipoint(positions: &[Position]) -> String {
+    if positions.is_empty() {
+        return "MULTIPOINT EMPTY".to_string();
+    }
+
+    let points: Vec<String> = positions
+        .iter()
+        .map(|p| format!("({})", p.wkt_coordinates()))
+        .collect();
+    format!("MULTIPOINT({})", points.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let position = Position::new(-43.536667, 172.632222);
+        assert_eq!(position.to_wkt(), "POINT(172.632222 -43.536667)");
+        assert_eq!(Position::from_wkt(&position.to_wkt()), Ok(position));
+
+        // spacing and case variations
+        assert_eq!(
+            Position::from_wkt(" point ( 172.632222   -43.536667 ) "),
+            Ok(position)
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Position::from_wkt("").is_err());
+        assert!(Position::from_wkt("POINT").is_err());
+        assert!(Position::from_wkt("POINT()").is_err());
+        assert!(Position::from_wkt("POINT(172.6)").is_err());
+        assert!(Position::from_wkt("POINT(172.6 -43.5 10)").is_err());
+        assert!(Position::from_wkt("POINT(abc -43.5)").is_err());
+        assert!(Position::from_wkt("POINT(172.6 -43.5").is_err());
+        assert!(Position::from_wkt("LINESTRING(172.6 -43.5)").is_err());
+        assert!(Position::from_wkt("POINT(-43.5 172.6)").is_err());
+    }
+
+    #[test]
+    fn multipoint() {
+        assert_eq!(positions_to_wkt_multipoint(&[]), "MULTIPOINT EMPTY");
+        assert_eq!(
+            positions_to_wkt_multipoint(&[Position::new(1.5, 2.0)]),
+            "MULTIPOINT((2 1.5))"
+        );
+    }
+}